@@ -0,0 +1,86 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Benchmarks hit tests against a full army on a 400x100 playfield; run with `cargo bench`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use terminal_invaders::config::Gameplay;
+use terminal_invaders::invaders::Invaders;
+use terminal_invaders::player::Player;
+use terminal_invaders::STEP_MILLIS;
+
+const DIMENSIONS: [u16; 2] = [400, 100];
+
+// Runs the benchmark for the given number of iterations; prints the time each one took.
+fn bench(name: &str, iterations: u32, mut run: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        run();
+    }
+    let each = start.elapsed() / iterations;
+    println!("{:<24} {:>10.1?} per iteration", name, each);
+}
+
+fn main() {
+    let gameplay = Gameplay {
+        shots: usize::from(DIMENSIONS[0]),
+        bomb_millis: 0,
+        ..Gameplay::default()
+    };
+    let army = Invaders::with_gameplay(&DIMENSIONS, &gameplay);
+    println!(
+        "{} invaders on a {}x{} playfield",
+        army.alive(),
+        DIMENSIONS[0],
+        DIMENSIONS[1]
+    );
+
+    // Looks up every cell of the playfield.
+    let mut invaders = Invaders::with_gameplay(&DIMENSIONS, &gameplay);
+    bench("hit test, every cell", 100, || {
+        for x in 0..DIMENSIONS[0] {
+            for y in 0..DIMENSIONS[1] {
+                black_box(invaders.hit_invader_at(x, y, 0));
+            }
+        }
+    });
+
+    // Sweeps every column from the bottom to the top.
+    bench("swept path, every column", 1000, || {
+        for x in 0..DIMENSIONS[0] {
+            black_box(invaders.in_path(x, 0, DIMENSIONS[1] - 1));
+        }
+    });
+
+    // Keeps a shot in the air over every column, and plays a while the way a game does.
+    let mut player = Player::new(&DIMENSIONS);
+    player.tune(&gameplay);
+    let mut invaders = Invaders::with_gameplay(&DIMENSIONS, &gameplay);
+    let delta = Duration::from_millis(STEP_MILLIS);
+    bench("bullet-heavy step", 2000, || {
+        while player.position().0 > 0 {
+            player.move_left();
+        }
+        for _ in 0..DIMENSIONS[0] {
+            player.shoot();
+            player.move_right();
+        }
+        player.update(delta);
+        invaders.update(delta);
+        black_box(player.detect_hit(&mut invaders));
+    });
+    println!("{} invaders left", invaders.alive());
+}
@@ -0,0 +1,246 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io;
+use std::process;
+
+use rusty_audio::Audio;
+
+use crate::{
+    synth::{Envelope, Patch, Tone, Waveform},
+    MARCH_BEATS,
+};
+
+// Provides all sound effects of the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sound {
+    Shoot,
+    Explode,
+    March(usize),
+    PlayerDeath,
+    WaveClear,
+    PowerUp,
+    GameOver,
+}
+
+// Implements the sound effects.
+impl Sound {
+
+    // Lists all sound effects.
+    pub const ALL: [Sound; 10] = [
+        Sound::Shoot,
+        Sound::Explode,
+        Sound::March(0),
+        Sound::March(1),
+        Sound::March(2),
+        Sound::March(3),
+        Sound::PlayerDeath,
+        Sound::WaveClear,
+        Sound::PowerUp,
+        Sound::GameOver,
+    ];
+
+    // Returns the name of the sound effect.
+    pub fn name(self) -> &'static str {
+        match self {
+            Sound::Shoot => "shoot",
+            Sound::Explode => "explode",
+            Sound::March(beat) => ["march_0", "march_1", "march_2", "march_3"][beat % MARCH_BEATS],
+            Sound::PlayerDeath => "player_death",
+            Sound::WaveClear => "wave_clear",
+            Sound::PowerUp => "power_up",
+            Sound::GameOver => "game_over",
+        }
+    }
+
+    // Returns the default synth description of the sound effect.
+    pub fn patch(self) -> Patch {
+        match self {
+
+            // A laser zapping down from a high pitch.
+            Sound::Shoot => {
+                Patch::sequence(vec![Tone::new(Waveform::Square, 1400.0, 300.0, 0.12, 0.3)])
+            }
+
+            // A burst of noise with a low thump.
+            Sound::Explode => Patch::layered(vec![
+                vec![Tone::new(Waveform::Noise, 0.0, 0.0, 0.3, 0.5)],
+                vec![Tone::new(Waveform::Sine, 120.0, 40.0, 0.2, 0.5)],
+            ]),
+
+            // Four descending bass notes, one per beat.
+            Sound::March(beat) => {
+                let pitch = [98.0, 87.0, 78.0, 73.0][beat % MARCH_BEATS];
+                Patch::sequence(vec![Tone::new(Waveform::Square, pitch, pitch, 0.09, 0.4)])
+            }
+
+            // A long warbling fall into noise.
+            Sound::PlayerDeath => Patch::layered(vec![
+                vec![Tone::new(Waveform::Sawtooth, 440.0, 60.0, 0.8, 0.3).with_warble(12.0, 0.08)],
+                vec![Tone::new(Waveform::Noise, 0.0, 0.0, 0.8, 0.3)],
+            ]),
+
+            // A rising arpeggio.
+            Sound::WaveClear => Patch::sequence(vec![
+                Tone::new(Waveform::Square, 523.0, 523.0, 0.12, 0.25)
+                    .with_envelope(Envelope::hold()),
+                Tone::new(Waveform::Square, 659.0, 659.0, 0.12, 0.25)
+                    .with_envelope(Envelope::hold()),
+                Tone::new(Waveform::Square, 784.0, 784.0, 0.12, 0.25)
+                    .with_envelope(Envelope::hold()),
+                Tone::new(Waveform::Square, 1047.0, 1047.0, 0.25, 0.25),
+            ]),
+
+            // A quick chirp sweeping up.
+            Sound::PowerUp => Patch::sequence(vec![
+                Tone::new(Waveform::Sine, 660.0, 1320.0, 0.08, 0.3).with_envelope(Envelope::hold()),
+                Tone::new(Waveform::Sine, 990.0, 1980.0, 0.12, 0.3),
+            ]),
+
+            // Three falling notes.
+            Sound::GameOver => Patch::sequence(vec![
+                Tone::new(Waveform::Triangle, 392.0, 392.0, 0.25, 0.5)
+                    .with_envelope(Envelope::hold()),
+                Tone::new(Waveform::Triangle, 330.0, 330.0, 0.25, 0.5)
+                    .with_envelope(Envelope::hold()),
+                Tone::new(Waveform::Triangle, 262.0, 200.0, 0.5, 0.5),
+            ]),
+        }
+    }
+}
+
+// Provides a sink that sound effects can be played on.
+pub trait AudioSink {
+
+    // Plays the given sound effect.
+    fn play(&mut self, sound: Sound);
+
+    // Blocks until all sound effects finished playing.
+    fn wait(&self);
+
+    // Determines whether the sink makes any sound at all.
+    fn audible(&self) -> bool {
+        true
+    }
+}
+
+// Provides a sink without any sound device that silently drops all sound effects.
+pub struct NullSink;
+
+// Implements the audio sink trait for the null sink.
+impl AudioSink for NullSink {
+    fn play(&mut self, _sound: Sound) {}
+
+    fn wait(&self) {}
+
+    fn audible(&self) -> bool {
+        false
+    }
+}
+
+// Provides a sink playing sound effects on the default sound device.
+pub struct SpeakerSink {
+    audio: Audio,
+}
+
+// Implements the speaker sink.
+impl SpeakerSink {
+
+    // Opens the default sound device with the sounds synthesized from the given patches.
+    // Returns none if there is no such device.
+    pub fn open(patch: impl Fn(Sound) -> Patch) -> io::Result<Option<Self>> {
+        let mut audio = Audio::new();
+        if audio.disabled() {
+            return Ok(None);
+        }
+
+        // Passes the synthesized sounds through files since clips can only be loaded from those.
+        let dir = std::env::temp_dir().join(format!("terminal_invaders-{}", process::id()));
+        fs::create_dir_all(&dir)?;
+        let loaded = Sound::ALL.iter().try_for_each(|sound| {
+            let path = dir.join(format!("{}.wav", sound.name()));
+            fs::write(&path, patch(*sound).to_wav())?;
+            let path: &str = &path.to_string_lossy();
+            audio.add(sound.name(), path);
+            Ok::<(), io::Error>(())
+        });
+        fs::remove_dir_all(&dir)?;
+        loaded?;
+        Ok(Some(Self { audio }))
+    }
+}
+
+// Implements the audio sink trait for the speakers.
+impl AudioSink for SpeakerSink {
+    fn play(&mut self, sound: Sound) {
+        self.audio.play(sound.name());
+    }
+
+    fn wait(&self) {
+        self.audio.wait();
+    }
+}
+
+// Provides the audio subsystem that can be muted at any time.
+pub struct AudioSystem {
+    sink: Box<dyn AudioSink>,
+    muted: bool,
+}
+
+// Implements the audio subsystem.
+impl AudioSystem {
+
+    // Creates a new audio subsystem playing on the given sink.
+    pub fn new(sink: Box<dyn AudioSink>, muted: bool) -> Self {
+        Self { sink, muted }
+    }
+
+    // Creates a new audio subsystem on the speakers, falling back to the null sink.
+    pub fn open(muted: bool, patch: impl Fn(Sound) -> Patch) -> Self {
+        match SpeakerSink::open(patch) {
+            Ok(Some(sink)) => Self::new(Box::new(sink), muted),
+            _ => Self::new(Box::new(NullSink), muted),
+        }
+    }
+
+    // Plays the given sound effect unless muted.
+    pub fn play(&mut self, sound: Sound) {
+        if !self.muted {
+            self.sink.play(sound);
+        }
+    }
+
+    // Determines whether the audio is muted.
+    pub fn muted(&self) -> bool {
+        self.muted
+    }
+
+    // Determines whether sound effects can be heard, i.e., not muted and not the null sink.
+    pub fn audible(&self) -> bool {
+        !self.muted && self.sink.audible()
+    }
+
+    // Toggles between muted and unmuted.
+    pub fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+    }
+
+    // Blocks until all sound effects finished playing.
+    pub fn wait(&self) {
+        if !self.muted {
+            self.sink.wait();
+        }
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor::MoveTo,
+    style::{Color, Print, SetBackgroundColor, SetForegroundColor},
+    terminal::{Clear, ClearType},
+    QueueableCommand,
+};
+
+use crate::config::Theme;
+
+// Provides a rendering backend that frames can be drawn on cell by cell.
+pub trait Backend {
+
+    // Resets the entire canvas.
+    fn clear(&mut self) -> io::Result<()>;
+
+    // Draws the given string at the position x, y.
+    fn draw(&mut self, x: u16, y: u16, s: &str) -> io::Result<()>;
+
+    // Presents everything drawn since the last flush.
+    fn flush(&mut self) -> io::Result<()>;
+}
+
+// Provides a backend writing crossterm commands to a terminal.
+pub struct CrosstermBackend<W: Write> {
+    out: W,
+    border: Color,
+    background: Color,
+    foreground: Option<Color>,
+}
+
+// Implements the crossterm backend.
+impl<W: Write> CrosstermBackend<W> {
+
+    // Creates a new crossterm backend on top of the given writer.
+    pub fn new(out: W) -> Self {
+        Self::themed(out, &Theme::default())
+    }
+
+    // Creates a new crossterm backend painting with the colors of the theme.
+    pub fn themed(out: W, theme: &Theme) -> Self {
+        let mut backend = Self {
+            out,
+            border: Color::Reset,
+            background: Color::Reset,
+            foreground: None,
+        };
+        backend.set_theme(theme);
+        backend
+    }
+
+    // Switches to the colors of the theme; they apply from the next clear on.
+    pub fn set_theme(&mut self, theme: &Theme) {
+        self.border = theme.border;
+        self.background = theme.background;
+        self.foreground = theme.foreground;
+    }
+}
+
+// Implements the backend trait for crossterm.
+impl<W: Write> Backend for CrosstermBackend<W> {
+
+    // Clears the terminal to the border color, e.g., grey, and paints on the background color;
+    // the foreground stays the terminal's default unless the theme picks one.
+    fn clear(&mut self) -> io::Result<()> {
+        self.out.queue(SetBackgroundColor(self.border))?;
+        self.out.queue(Clear(ClearType::All))?;
+        self.out.queue(SetBackgroundColor(self.background))?;
+        self.out
+            .queue(SetForegroundColor(self.foreground.unwrap_or(Color::Reset)))?;
+        Ok(())
+    }
+
+    // Moves the cursor to x, y and prints the string.
+    fn draw(&mut self, x: u16, y: u16, s: &str) -> io::Result<()> {
+        self.out.queue(MoveTo(x, y))?;
+        self.out.queue(Print(s))?;
+        Ok(())
+    }
+
+    // Flushes all queued commands to the terminal.
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+// Provides an in-memory backend that keeps a grid of characters and a log of draw calls, e.g.,
+// for tests.
+pub struct MemoryBackend {
+    width: u16,
+    height: u16,
+    cells: Vec<Vec<char>>,
+    draws: Vec<(u16, u16, String)>,
+}
+
+// Implements the in-memory backend.
+impl MemoryBackend {
+
+    // Creates a new blank in-memory grid with the given dimensions.
+    pub fn new(dimensions: &[u16]) -> Self {
+        let width = dimensions[0];
+        let height = dimensions[1];
+        Self {
+            width,
+            height,
+            cells: vec![vec![' '; width.into()]; height.into()],
+            draws: Vec::new(),
+        }
+    }
+
+    // Takes the draw calls made since it was last asked, as x, y, and the string drawn.
+    pub fn take_draws(&mut self) -> Vec<(u16, u16, String)> {
+        std::mem::take(&mut self.draws)
+    }
+
+    // Returns the character at the position x, y.
+    pub fn cell(&self, x: u16, y: u16) -> char {
+        self.cells[y as usize][x as usize]
+    }
+
+    // Returns the grid as text with one line per row.
+    pub fn to_text(&self) -> String {
+        let mut text = String::with_capacity(self.cells.len() * (self.width as usize + 1));
+        for row in self.cells.iter() {
+            text.extend(row.iter());
+            text.push('\n');
+        }
+        text
+    }
+}
+
+// Implements the backend trait for the in-memory grid.
+impl Backend for MemoryBackend {
+
+    // Blanks all cells.
+    fn clear(&mut self) -> io::Result<()> {
+        for row in self.cells.iter_mut() {
+            row.fill(' ');
+        }
+        Ok(())
+    }
+
+    // Writes the string like a terminal would; longer strings run into the next cells.
+    fn draw(&mut self, x: u16, y: u16, s: &str) -> io::Result<()> {
+        self.draws.push((x, y, s.to_string()));
+        if y >= self.height {
+            return Ok(());
+        }
+        let row = &mut self.cells[y as usize];
+        for (offset, c) in s.chars().enumerate() {
+            match row.get_mut(x as usize + offset) {
+                Some(cell) => *cell = c,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    // Has nothing to present.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Provides a backend dumping every presented frame as plain text.
+pub struct TextBackend<W: Write> {
+    grid: MemoryBackend,
+    out: W,
+}
+
+// Implements the plain-text backend.
+impl<W: Write> TextBackend<W> {
+
+    // Creates a new plain-text backend with the given dimensions on top of the writer.
+    pub fn new(dimensions: &[u16], out: W) -> Self {
+        Self {
+            grid: MemoryBackend::new(dimensions),
+            out,
+        }
+    }
+}
+
+// Implements the backend trait for plain-text dumps.
+impl<W: Write> Backend for TextBackend<W> {
+
+    // Blanks the underlying grid.
+    fn clear(&mut self) -> io::Result<()> {
+        self.grid.clear()
+    }
+
+    // Draws on the underlying grid.
+    fn draw(&mut self, x: u16, y: u16, s: &str) -> io::Result<()> {
+        self.grid.draw(x, y, s)
+    }
+
+    // Writes the grid followed by a form feed separating the frames, and forgets the draw calls.
+    fn flush(&mut self) -> io::Result<()> {
+        self.grid.take_draws();
+        self.out.write_all(self.grid.to_text().as_bytes())?;
+        self.out.write_all(b"\x0c\n")?;
+        self.out.flush()
+    }
+}
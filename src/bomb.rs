@@ -0,0 +1,64 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use rusty_time::prelude::Timer;
+
+use crate::{
+    config::Theme,
+    frame::{Drawable, Frame},
+    BOMB_FALL_MILLIS,
+};
+
+// Provides a struct for bombs dropped by the invaders.
+pub struct Bomb {
+    pub x: u16,
+    pub y: u16,
+    timer: Timer,
+}
+
+// Implements the bomb struct.
+impl Bomb {
+
+    // Creates a new bomb at the given position.
+    pub fn new(x: u16, y: u16) -> Self {
+        Self {
+            x,
+            y,
+
+            // It falls with a speed of 1/100ms.
+            timer: Timer::from_millis(BOMB_FALL_MILLIS),
+        }
+    }
+
+    // Updates the bomb's position; returns whether it is still within the given height.
+    pub fn update(&mut self, delta: Duration, height: u16) -> bool {
+        self.timer.update(delta);
+        if self.timer.ready {
+            self.timer.reset();
+            self.y += 1;
+        }
+        self.y < height
+    }
+}
+
+// Implements the drawable trait for bombs.
+impl Drawable for Bomb {
+
+    // Draws a bomb on a given frame.
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        frame[self.x as usize][self.y as usize] = theme.bomb.clone();
+    }
+}
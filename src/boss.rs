@@ -0,0 +1,293 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use rusty_time::prelude::Timer;
+
+use crate::{
+    config::Theme,
+    frame::{Drawable, Frame},
+    rng::Rng,
+};
+
+// Provides the crates the fake build compiles, with their versions.
+const CRATES: [(&str, &str); 40] = [
+    ("proc-macro2", "1.0.36"),
+    ("unicode-xid", "0.2.2"),
+    ("libc", "0.2.119"),
+    ("syn", "1.0.86"),
+    ("autocfg", "1.1.0"),
+    ("cfg-if", "1.0.0"),
+    ("memchr", "2.4.1"),
+    ("log", "0.4.14"),
+    ("serde", "1.0.136"),
+    ("quote", "1.0.15"),
+    ("version_check", "0.9.4"),
+    ("once_cell", "1.9.0"),
+    ("itoa", "1.0.1"),
+    ("ryu", "1.0.9"),
+    ("bitflags", "1.3.2"),
+    ("lazy_static", "1.4.0"),
+    ("smallvec", "1.8.0"),
+    ("scopeguard", "1.1.0"),
+    ("lock_api", "0.4.6"),
+    ("parking_lot_core", "0.9.1"),
+    ("parking_lot", "0.12.0"),
+    ("serde_derive", "1.0.136"),
+    ("serde_json", "1.0.79"),
+    ("bytes", "1.1.0"),
+    ("pin-project-lite", "0.2.8"),
+    ("futures-core", "0.3.21"),
+    ("futures-channel", "0.3.21"),
+    ("futures-util", "0.3.21"),
+    ("mio", "0.8.0"),
+    ("socket2", "0.4.4"),
+    ("tokio-macros", "1.7.0"),
+    ("tokio", "1.17.0"),
+    ("tracing-core", "0.1.22"),
+    ("tracing", "0.1.31"),
+    ("http", "0.2.6"),
+    ("httparse", "1.6.0"),
+    ("h2", "0.3.11"),
+    ("hyper", "0.14.17"),
+    ("regex-syntax", "0.6.25"),
+    ("regex", "1.5.4"),
+];
+
+// Provides the end of a build log, as many lines as fit on the screen.
+struct Log {
+    lines: VecDeque<String>,
+    rows: usize,
+}
+
+// Implements the build log.
+impl Log {
+
+    // Creates a new empty log keeping the given number of lines.
+    fn new(rows: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(rows),
+            rows,
+        }
+    }
+
+    // Appends a line, dropping the oldest one once the screen is full.
+    fn push(&mut self, line: String) {
+        if self.lines.len() == self.rows {
+            self.lines.pop_front();
+        }
+        if self.rows > 0 {
+            self.lines.push_back(line);
+        }
+    }
+
+    // Appends the given lines one after another.
+    fn extend(&mut self, lines: impl IntoIterator<Item = String>) {
+        for line in lines {
+            self.push(line);
+        }
+    }
+}
+
+// Provides a boss screen that hides the game behind a scrolling build log.
+pub struct BossScreen {
+    pub visible: bool,
+    log: Arc<Mutex<Log>>,
+    wrapped: bool,
+    fake: FakeBuild,
+}
+
+// Implements the boss screen.
+impl BossScreen {
+
+    // Creates a new hidden boss screen with a convincing, but fake, build log that fills the
+    // given dimensions.
+    pub fn new(dimensions: &[u16]) -> Self {
+        Self {
+            visible: false,
+            log: Arc::new(Mutex::new(Log::new(dimensions[1].into()))),
+            wrapped: false,
+            fake: FakeBuild::new(),
+        }
+    }
+
+    // Creates a new hidden boss screen that shows the real output of the given shell command,
+    // which starts right away, e.g., `cargo build`. The command is left running when the game
+    // quits; the game's ends of its output pipes close then, so the command gets a broken pipe
+    // on its next write.
+    pub fn wrap(command: &str, dimensions: &[u16]) -> io::Result<Self> {
+        let (shell, flag) = if cfg!(windows) {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+        let mut child = Command::new(shell)
+            .args([flag, command])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let boss = Self {
+            wrapped: true,
+            ..Self::new(dimensions)
+        };
+        if let Some(stdout) = child.stdout.take() {
+            collect_lines(stdout, Arc::clone(&boss.log));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            collect_lines(stderr, Arc::clone(&boss.log));
+        }
+
+        // Reaps the command once it is done, so that it does not linger as a zombie.
+        thread::spawn(move || child.wait());
+        Ok(boss)
+    }
+
+    // Shows or hides the boss screen.
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    // Continues the fake build while the boss screen is visible.
+    pub fn update(&mut self, delta: Duration) {
+        if self.visible && !self.wrapped {
+            if let Ok(mut log) = self.log.lock() {
+                self.fake.update(delta, &mut log);
+            }
+        }
+    }
+
+    // Returns the lines of the build log that are kept.
+    pub fn lines(&self) -> Vec<String> {
+        self.log
+            .lock()
+            .map_or(Vec::new(), |log| log.lines.iter().cloned().collect())
+    }
+}
+
+// Implements the drawable trait for the boss screen.
+impl Drawable for BossScreen {
+
+    // Draws the end of the build log over the entire frame.
+    fn draw(&self, frame: &mut Frame, _theme: &Theme) {
+        if !self.visible {
+            return;
+        }
+        for cell in frame.iter_mut().flat_map(|col| col.iter_mut()) {
+            *cell = " ".to_string();
+        }
+        let rows = frame.first().map_or(0, |col| col.len());
+        let log = match self.log.lock() {
+            Ok(log) => log,
+            Err(_) => return,
+        };
+        let start = log.lines.len().saturating_sub(rows);
+        for (y, line) in log.lines.iter().skip(start).enumerate() {
+            for (col, c) in frame.iter_mut().zip(line.chars()) {
+                col[y] = c.to_string();
+            }
+        }
+    }
+}
+
+// Appends every line of the output to the log in the background.
+fn collect_lines<R: Read + Send + 'static>(output: R, log: Arc<Mutex<Log>>) {
+    thread::spawn(move || {
+        for line in BufReader::new(output).lines().map_while(Result::ok) {
+            if let Ok(mut log) = log.lock() {
+                log.push(line.replace('\t', "    "));
+            }
+        }
+    });
+}
+
+// Provides a fake cargo build that never quite finishes.
+struct FakeBuild {
+    next: usize,
+    timer: Timer,
+    seconds: f64,
+    rng: Rng,
+}
+
+// Implements the fake build.
+impl FakeBuild {
+
+    // Creates a new fake build that starts with the first crate.
+    fn new() -> Self {
+        Self {
+            next: 0,
+            timer: Timer::from_millis(0),
+
+            // Pretends that the build has been running for a while.
+            seconds: 31.5,
+            rng: Rng::new(0),
+        }
+    }
+
+    // Appends the lines the build printed in the meantime.
+    fn update(&mut self, delta: Duration, log: &mut Log) {
+        self.seconds += delta.as_secs_f64();
+
+        // Starts with a screen full of crates that were compiled already.
+        if log.lines.is_empty() {
+            log.push("    Updating crates.io index".to_string());
+            for _ in 0..CRATES.len() / 2 {
+                self.step(log);
+            }
+        }
+        self.timer.update(delta);
+        if self.timer.ready {
+            self.step(log);
+            self.timer = Timer::from_millis(80 + self.rng.below(600));
+        }
+    }
+
+    // Appends the next crate, or finishes the build and starts a rebuild of the app.
+    fn step(&mut self, log: &mut Log) {
+        match CRATES.get(self.next) {
+            Some((name, version)) => log.push(format!("   Compiling {} v{}", name, version)),
+            None if self.next == CRATES.len() => {
+                log.push("   Compiling app v0.1.0 (/home/dev/app)".to_string())
+            }
+            None => {
+                log.extend([
+                    "warning: unused variable: `frame`".to_string(),
+                    "  --> src/render.rs:75:50".to_string(),
+                    "   |".to_string(),
+                    "75 |     pub fn render(&mut self, frame: &Frame) {".to_string(),
+                    "   |                              ^^^^^ help: if this is intentional, \
+                     prefix it with an underscore: `_frame`"
+                        .to_string(),
+                    "   |".to_string(),
+                    "   = note: `#[warn(unused_variables)]` on by default".to_string(),
+                    String::new(),
+                    "warning: `app` (lib) generated 1 warning".to_string(),
+                    format!(
+                        "    Finished `dev` profile [unoptimized + debuginfo] target(s) in {:.2}s",
+                        self.seconds
+                    ),
+                ]);
+                self.next = CRATES.len() - 1;
+                self.seconds = 0.0;
+            }
+        }
+        self.next += 1;
+    }
+}
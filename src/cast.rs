@@ -0,0 +1,130 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// Provides a recorder writing terminal output as asciicast v2 events.
+pub struct CastRecorder<W: Write> {
+    out: W,
+    start: Instant,
+}
+
+// Implements the asciicast recorder.
+impl<W: Write> CastRecorder<W> {
+
+    // Creates a new recorder and writes the asciicast header for the given dimensions.
+    pub fn new(mut out: W, dimensions: &[u16]) -> io::Result<Self> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        writeln!(
+            out,
+            "{{\"version\": 2, \"width\": {}, \"height\": {}, \"timestamp\": {}, \"title\": \"Terminal Invaders\"}}",
+            dimensions[0], dimensions[1], timestamp
+        )?;
+        Ok(Self {
+            out,
+            start: Instant::now(),
+        })
+    }
+
+    // Records output written to the terminal.
+    pub fn output(&mut self, data: &[u8]) -> io::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let text = String::from_utf8_lossy(data);
+        self.event("o", &text)
+    }
+
+    // Records a resize of the terminal.
+    pub fn resize(&mut self, cols: u16, rows: u16) -> io::Result<()> {
+        self.event("r", &format!("{}x{}", cols, rows))
+    }
+
+    // Writes a single event line with the time elapsed since the recording started.
+    fn event(&mut self, code: &str, data: &str) -> io::Result<()> {
+        let time = self.start.elapsed().as_secs_f64();
+        writeln!(
+            self.out,
+            "[{:.6}, \"{}\", \"{}\"]",
+            time,
+            code,
+            escape(data)
+        )?;
+        self.out.flush()
+    }
+}
+
+// Provides a writer passing output to the terminal while recording it.
+pub struct CastWriter<T: Write, W: Write> {
+    terminal: T,
+    recorder: Arc<Mutex<CastRecorder<W>>>,
+    buffer: Vec<u8>,
+}
+
+// Implements the recording writer.
+impl<T: Write, W: Write> CastWriter<T, W> {
+
+    // Creates a new writer in front of the terminal sharing the given recorder.
+    pub fn new(terminal: T, recorder: Arc<Mutex<CastRecorder<W>>>) -> Self {
+        Self {
+            terminal,
+            recorder,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+// Implements the write trait; everything written until a flush becomes one event.
+impl<T: Write, W: Write> Write for CastWriter<T, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.terminal.write(buf)?;
+        self.buffer.extend_from_slice(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.terminal.flush()?;
+        let mut recorder = self
+            .recorder
+            .lock()
+            .map_err(|_| io::Error::other("The recorder should not be poisoned."))?;
+        recorder.output(&self.buffer)?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+// Escapes a string to be embedded in a JSON string literal.
+fn escape(data: &str) -> String {
+    let mut escaped = String::with_capacity(data.len());
+    for c in data.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,364 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crossterm::style::Color;
+use rusty_time::prelude::Timer;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audio::Sound,
+    difficulty::Difficulty,
+    keys::{KeyConfig, KeyMap},
+    synth::Patch,
+    ARMY_SPACING, BOMB_MILLIS, EXPLOSION_MILLIS, LIVES, MARCH_MILLIS, MARCH_SPEEDUP_MILLIS,
+    MIN_MARCH_MILLIS, MOVE_MILLIS, NUM_SHOTS, SHOT_MILLIS,
+};
+
+// Provides the configuration of the game as read from the TOML file.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub gameplay: GameplayConfig,
+    pub theme: Theme,
+    pub controls: KeyConfig,
+    pub sound: SoundConfig,
+}
+
+// Provides the gameplay settings: a difficulty preset, and single values overriding it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameplayConfig {
+    pub difficulty: Difficulty,
+    pub adaptive: bool,
+    pub shots: Option<usize>,
+    pub march_millis: Option<u64>,
+    pub march_speedup_millis: Option<u64>,
+    pub min_march_millis: Option<u64>,
+    pub shot_millis: Option<u64>,
+    pub explosion_millis: Option<u64>,
+    pub move_millis: Option<u64>,
+    pub army_spacing: Option<u16>,
+    pub bomb_millis: Option<u64>,
+    pub lives: Option<u32>,
+}
+
+// Implements the gameplay settings.
+impl GameplayConfig {
+
+    // Returns the tuning of the difficulty with all overrides applied.
+    pub fn resolve(&self) -> Gameplay {
+        let preset = self.difficulty.gameplay();
+        Gameplay {
+            shots: self.shots.unwrap_or(preset.shots),
+            march_millis: self.march_millis.unwrap_or(preset.march_millis),
+            march_speedup_millis: self
+                .march_speedup_millis
+                .unwrap_or(preset.march_speedup_millis),
+            min_march_millis: self.min_march_millis.unwrap_or(preset.min_march_millis),
+            shot_millis: self.shot_millis.unwrap_or(preset.shot_millis),
+            explosion_millis: self.explosion_millis.unwrap_or(preset.explosion_millis),
+            move_millis: self.move_millis.unwrap_or(preset.move_millis),
+            army_spacing: self.army_spacing.unwrap_or(preset.army_spacing),
+            bomb_millis: self.bomb_millis.unwrap_or(preset.bomb_millis),
+            lives: self.lives.unwrap_or(preset.lives),
+        }
+    }
+}
+
+// Provides the tuning of the game, as resolved from the settings and stored with replays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Gameplay {
+    pub shots: usize,
+    pub march_millis: u64,
+    pub march_speedup_millis: u64,
+    pub min_march_millis: u64,
+    pub shot_millis: u64,
+    pub explosion_millis: u64,
+    pub move_millis: u64,
+    pub army_spacing: u16,
+    pub bomb_millis: u64,
+    pub lives: u32,
+}
+
+// Implements the default tuning, which is the normal difficulty.
+impl Default for Gameplay {
+    fn default() -> Self {
+        Self {
+            shots: NUM_SHOTS,
+            march_millis: MARCH_MILLIS,
+            march_speedup_millis: MARCH_SPEEDUP_MILLIS,
+            min_march_millis: MIN_MARCH_MILLIS,
+            shot_millis: SHOT_MILLIS,
+            explosion_millis: EXPLOSION_MILLIS,
+            move_millis: MOVE_MILLIS,
+            army_spacing: ARMY_SPACING,
+            bomb_millis: BOMB_MILLIS,
+            lives: LIVES,
+        }
+    }
+}
+
+// Implements the tuning.
+impl Gameplay {
+
+    // Checks that the tuning keeps the game playable.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [
+            ("march_millis", self.march_millis),
+            ("min_march_millis", self.min_march_millis),
+            ("shot_millis", self.shot_millis),
+            ("explosion_millis", self.explosion_millis),
+            ("move_millis", self.move_millis),
+        ] {
+            if value == 0 {
+                return Err(format!("gameplay.{} must be greater than 0", name));
+            }
+        }
+        if self.shots == 0 {
+            return Err("gameplay.shots must be greater than 0".to_string());
+        }
+        if self.army_spacing == 0 {
+            return Err("gameplay.army_spacing must be greater than 0".to_string());
+        }
+        if self.lives == 0 {
+            return Err("gameplay.lives must be greater than 0".to_string());
+        }
+        if self.min_march_millis > self.march_millis {
+            return Err(format!(
+                "gameplay.min_march_millis ({}) must not exceed gameplay.march_millis ({})",
+                self.min_march_millis, self.march_millis
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Provides the colors and glyphs of the game.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    pub border: Color,
+    pub background: Color,
+    pub foreground: Option<Color>,
+    pub player: String,
+    pub invader: String,
+    pub invader_alt: String,
+    pub shot: String,
+    pub explosion: String,
+    pub bomb: String,
+}
+
+// Implements the default theme.
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            border: Color::Grey,
+            background: Color::Black,
+            foreground: None,
+            player: "△".to_string(),
+            invader: "■".to_string(),
+            invader_alt: "□".to_string(),
+            shot: "◦".to_string(),
+            explosion: "◉".to_string(),
+            bomb: "▾".to_string(),
+        }
+    }
+}
+
+// Implements the theme.
+impl Theme {
+
+    // Checks that every glyph fills exactly one cell.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, glyph) in self.glyphs() {
+            if glyph.chars().count() != 1 {
+                return Err(format!(
+                    "theme.{} must be a single character, got '{}'",
+                    name, glyph
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Lists the glyphs by their names.
+    fn glyphs(&self) -> [(&'static str, &str); 6] {
+        [
+            ("player", &self.player),
+            ("invader", &self.invader),
+            ("invader_alt", &self.invader_alt),
+            ("shot", &self.shot),
+            ("explosion", &self.explosion),
+            ("bomb", &self.bomb),
+        ]
+    }
+}
+
+// Provides the sound settings, including tweaked sound effects.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SoundConfig {
+    pub muted: bool,
+    pub effects: BTreeMap<String, Patch>,
+}
+
+// Implements the sound settings.
+impl SoundConfig {
+
+    // Checks that every tweaked sound effect exists and can be played.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, patch) in self.effects.iter() {
+            if !Sound::ALL.iter().any(|sound| sound.name() == name) {
+                let names: Vec<&str> = Sound::ALL.iter().map(|sound| sound.name()).collect();
+                return Err(format!(
+                    "unknown sound effect sound.effects.{}, expected one of {}",
+                    name,
+                    names.join(", ")
+                ));
+            }
+            patch
+                .validate()
+                .map_err(|error| format!("sound.effects.{}: {}", name, error))?;
+        }
+        Ok(())
+    }
+
+    // Returns the synth description of the sound effect, tweaked or default.
+    pub fn patch(&self, sound: Sound) -> Patch {
+        self.effects
+            .get(sound.name())
+            .cloned()
+            .unwrap_or_else(|| sound.patch())
+    }
+}
+
+// Implements the configuration.
+impl Config {
+
+    // Parses and validates a configuration.
+    pub fn parse(contents: &str) -> Result<Self, Box<dyn Error>> {
+        let config: Config = toml::from_str(contents)?;
+        config.gameplay().validate()?;
+        config.theme.validate()?;
+        config.sound.validate()?;
+        config.key_map()?;
+        Ok(config)
+    }
+
+    // Loads the configuration from the given file, or from the default location if there is
+    // none; the default location may also be missing, the given file may not.
+    pub fn load(path: Option<&Path>) -> Result<Self, Box<dyn Error>> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Config::default()),
+            },
+        };
+        match fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents)
+                .map_err(|error| format!("{}: {}", path.display(), error).into()),
+            Err(error) if error.kind() == io::ErrorKind::NotFound && !required => {
+                Ok(Config::default())
+            }
+            Err(error) => Err(format!("{}: {}", path.display(), error).into()),
+        }
+    }
+
+    // Returns the tuning of the game.
+    pub fn gameplay(&self) -> Gameplay {
+        self.gameplay.resolve()
+    }
+
+    // Returns the key bindings of the controls.
+    pub fn key_map(&self) -> Result<KeyMap, Box<dyn Error>> {
+        self.controls.clone().into_key_map()
+    }
+}
+
+// Provides a watcher that reloads the configuration file whenever it changes.
+pub struct ConfigWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    timer: Timer,
+}
+
+// Implements the configuration watcher.
+impl ConfigWatcher {
+
+    // Creates a new watcher for the given file, which does not need to exist yet.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            modified: modified(&path),
+            path,
+
+            // Checks the file twice a second.
+            timer: Timer::from_millis(500),
+        }
+    }
+
+    // Returns the watched file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Returns the reloaded configuration or the reason it is invalid if the file changed.
+    pub fn poll(&mut self, delta: Duration) -> Option<Result<Config, String>> {
+        self.timer.update(delta);
+        if !self.timer.ready {
+            return None;
+        }
+        self.timer.reset();
+
+        // Keeps the current configuration if the file vanished.
+        let modified = modified(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(Config::load(Some(&self.path)).map_err(|error| error.to_string()))
+    }
+}
+
+// Returns when the file was modified last, if it exists.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+// Returns the default location of the configuration file, following the XDG base directories.
+pub fn default_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("terminal_invaders").join("config.toml"))
+}
+
+// Returns the user's configuration directory.
+fn config_dir() -> Option<PathBuf> {
+    let non_empty = |name: &str| env::var_os(name).filter(|value| !value.is_empty());
+    if cfg!(windows) {
+        return non_empty("APPDATA").map(PathBuf::from);
+    }
+    non_empty("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| non_empty("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
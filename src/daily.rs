@@ -0,0 +1,97 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{date::Date, leaderboard};
+
+// Returns the seed of the daily challenge on the given date, the same for everyone.
+pub fn seed(date: Date) -> u64 {
+
+    // Mixes the day with SplitMix64, so that neighboring days play nothing alike.
+    let mut seed = (date.days() as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    seed = (seed ^ (seed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    seed = (seed ^ (seed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    seed ^ (seed >> 31)
+}
+
+// Provides the contents of the file remembering the last scored daily challenge.
+#[derive(Debug, Serialize, Deserialize)]
+struct Attempts {
+    last_scored: Option<String>,
+}
+
+// Provides the local log of daily challenges, which allows one scored attempt per day.
+pub struct DailyLog {
+    path: PathBuf,
+}
+
+// Implements the log of daily challenges.
+impl DailyLog {
+
+    // Creates a log stored in the given file.
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    // Determines whether the scored attempt of the given day is still available, or whether an
+    // attempt is only practice. A missing log is empty; a corrupted one is moved aside, so that
+    // it does not cost the day's attempt.
+    pub fn available(&self, date: Date) -> io::Result<bool> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(true),
+            Err(error) => return Err(error),
+        };
+        match toml::from_str::<Attempts>(&contents) {
+            Ok(attempts) => Ok(attempts.last_scored != Some(date.to_string())),
+            Err(_) => {
+                fs::rename(&self.path, self.path.with_extension("toml.corrupt"))?;
+                Ok(true)
+            }
+        }
+    }
+
+    // Takes the scored attempt of the given day if it is still available; returns whether it
+    // was. The attempt is used up as its game starts, so that quitting it early grants no other.
+    pub fn claim(&self, date: Date) -> io::Result<bool> {
+        let available = self.available(date)?;
+        if available {
+            self.spend(date)?;
+        }
+        Ok(available)
+    }
+
+    // Uses up the scored attempt of the given day.
+    pub fn spend(&self, date: Date) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let attempts = Attempts {
+            last_scored: Some(date.to_string()),
+        };
+        let contents = toml::to_string(&attempts)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        fs::write(&self.path, contents)
+    }
+}
+
+// Returns the default location of the log, next to the leaderboard.
+pub fn default_path() -> Option<PathBuf> {
+    leaderboard::default_path().map(|path| path.with_file_name("daily.toml"))
+}
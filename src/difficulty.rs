@@ -0,0 +1,206 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use rusty_time::prelude::Timer;
+use serde::Deserialize;
+
+use crate::config::Gameplay;
+
+// Provides the difficulty presets.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Insane,
+}
+
+// Provides the names of all difficulty presets.
+pub const DIFFICULTIES: [&str; 4] = ["easy", "normal", "hard", "insane"];
+
+// Implements the difficulty presets.
+impl Difficulty {
+
+    // Lists all difficulties from the easiest to the hardest.
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Insane,
+    ];
+
+    // Returns the name of the difficulty as used in the configuration file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Insane => "insane",
+        }
+    }
+
+    // Returns the tuning of the difficulty.
+    pub fn gameplay(self) -> Gameplay {
+        let normal = Gameplay::default();
+        match self {
+
+            // A sparse army that marches slowly and rarely bombs.
+            Difficulty::Easy => Gameplay {
+                shots: 12,
+                march_millis: 2500,
+                march_speedup_millis: 200,
+                min_march_millis: 400,
+                army_spacing: 3,
+                bomb_millis: 4000,
+                lives: 5,
+                ..normal
+            },
+            Difficulty::Normal => normal,
+
+            // A faster army that bombs twice as often.
+            Difficulty::Hard => Gameplay {
+                shots: 6,
+                march_millis: 1500,
+                min_march_millis: 150,
+                bomb_millis: 1000,
+                ..normal
+            },
+
+            // A packed army, a hail of bombs, and a single life.
+            Difficulty::Insane => Gameplay {
+                shots: 3,
+                march_millis: 1000,
+                march_speedup_millis: 200,
+                min_march_millis: 100,
+                army_spacing: 1,
+                bomb_millis: 400,
+                lives: 1,
+                ..normal
+            },
+        }
+    }
+}
+
+// Implements parsing difficulties from their names.
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL
+            .iter()
+            .copied()
+            .find(|difficulty| difficulty.name() == name)
+            .ok_or_else(|| format!("unknown difficulty '{}'", name))
+    }
+}
+
+// Provides an adaptive difficulty that nudges the march speed to the player's performance.
+pub struct Adaptive {
+    fired: u32,
+    hits: u32,
+    pace: f64,
+    timer: Timer,
+}
+
+// Implements the adaptive difficulty.
+impl Adaptive {
+
+    // Creates a new adaptive difficulty that starts at the configured pace.
+    pub fn new() -> Self {
+        Self {
+            fired: 0,
+            hits: 0,
+
+            // Scales the time between two steps of the army; below 1 is faster.
+            pace: 1.0,
+
+            // Judges the player's recent accuracy every 5 seconds.
+            timer: Timer::from_millis(5000),
+        }
+    }
+
+    // Returns the factor the time between two steps is scaled with.
+    pub fn pace(&self) -> f64 {
+        self.pace
+    }
+
+    // Counts a fired shot.
+    pub fn record_shot(&mut self) {
+        self.fired += 1;
+    }
+
+    // Counts a shot that hit an invader.
+    pub fn record_hit(&mut self) {
+        self.hits += 1;
+    }
+
+    // Slows the army down after the ship was bombed.
+    pub fn record_death(&mut self) {
+        self.set_pace(self.pace + 0.15);
+    }
+
+    // Speeds the army up for sharp shooters and slows it down for those who miss a lot;
+    // returns whether the pace changed.
+    pub fn update(&mut self, delta: Duration) -> bool {
+        self.timer.update(delta);
+        if !self.timer.ready {
+            return false;
+        }
+        self.timer.reset();
+        let before = self.pace;
+
+        // Needs a few shots to judge the accuracy.
+        if self.fired >= 4 {
+            let accuracy = f64::from(self.hits) / f64::from(self.fired);
+            if accuracy > 0.6 {
+                self.set_pace(self.pace - 0.05);
+            } else if accuracy < 0.25 {
+                self.set_pace(self.pace + 0.05);
+            }
+        }
+
+        // Lets older shots count less and less.
+        self.fired /= 2;
+        self.hits /= 2;
+        self.pace != before
+    }
+
+    // Returns the tuning with the march scaled to the current pace.
+    pub fn apply(&self, gameplay: &Gameplay) -> Gameplay {
+        let scale = |millis: u64| (millis as f64 * self.pace).round() as u64;
+        Gameplay {
+            march_millis: scale(gameplay.march_millis).max(1),
+            march_speedup_millis: scale(gameplay.march_speedup_millis),
+            min_march_millis: scale(gameplay.min_march_millis).max(1),
+            ..*gameplay
+        }
+    }
+
+    // Keeps the pace within reasonable bounds.
+    fn set_pace(&mut self, pace: f64) {
+        self.pace = pace.clamp(0.5, 1.5);
+    }
+}
+
+// Implements the default adaptive difficulty.
+impl Default for Adaptive {
+    fn default() -> Self {
+        Self::new()
+    }
+}
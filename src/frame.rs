@@ -12,11 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::config::Theme;
+
 // Provides a frame canvas as two-dimensional collection.
 pub type Frame = Vec<Vec<String>>;
 
 // Prepares an empty two-dimensional frame to be used to draw on.
-pub fn new_frame(dimensions: &Vec<u16>) -> Frame {
+pub fn new_frame(dimensions: &[u16]) -> Frame {
     let x = dimensions[0];
     let y = dimensions[1];
     let mut cols = Vec::with_capacity(x.into());
@@ -30,7 +32,37 @@ pub fn new_frame(dimensions: &Vec<u16>) -> Frame {
     cols
 }
 
-// Provides a drawable trait for all structs to be displayed on the frame.
+// Provides a drawable trait for all structs to be displayed on the frame, in the glyphs of the
+// given theme.
 pub trait Drawable {
-    fn draw(&self, frame: &mut Frame);
+    fn draw(&self, frame: &mut Frame, theme: &Theme);
+}
+
+// Draws the lines centered on a blank box, e.g., for the help screen.
+pub fn draw_box(frame: &mut Frame, lines: &[String]) {
+    let longest = lines.iter().map(|line| line.chars().count()).max();
+    let width = longest.unwrap_or(0) + 4;
+    let height = lines.len() + 2;
+    let rows = frame.first().map_or(0, |col| col.len());
+    let left = frame.len().saturating_sub(width) / 2;
+    let top = rows.saturating_sub(height) / 2;
+
+    // Blanks the box so the game does not shine through.
+    for col in frame.iter_mut().skip(left).take(width) {
+        for cell in col.iter_mut().skip(top).take(height) {
+            *cell = " ".to_string();
+        }
+    }
+
+    // Writes the lines character by character.
+    for (y, line) in lines.iter().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            if let Some(cell) = frame
+                .get_mut(left + 2 + x)
+                .and_then(|col| col.get_mut(top + 1 + y))
+            {
+                *cell = c.to_string();
+            }
+        }
+    }
 }
@@ -0,0 +1,421 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::{
+    audio::Sound,
+    config::{Gameplay, Theme},
+    difficulty::Adaptive,
+    frame::{Drawable, Frame},
+    invaders::Invaders,
+    keys::Action,
+    player::Player,
+    powerup::{Capsule, Power},
+    rng::Rng,
+    score::Scorecard,
+    steering::Steering,
+    POWER_UP_CHANCE, STEP_MILLIS, WAVES,
+};
+
+// Provides an input that changes the course of a game; the game logs all of them, so that the
+// run can be replayed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Input {
+    Press(Action),
+    Release(Action),
+    ReleaseAll,
+    Shoot,
+    CycleWeapon,
+    Aim(u16),
+}
+
+// Implements displaying inputs the way they are logged, e.g., `+left`, `-left`, `shoot`,
+// `weapon`, or `@12`.
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Input::Press(action) => write!(f, "+{}", action.name()),
+            Input::Release(action) => write!(f, "-{}", action.name()),
+            Input::ReleaseAll => f.write_str("-all"),
+            Input::Shoot => f.write_str("shoot"),
+            Input::CycleWeapon => f.write_str("weapon"),
+            Input::Aim(column) => write!(f, "@{}", column),
+        }
+    }
+}
+
+// Implements parsing logged inputs.
+impl FromStr for Input {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input == "-all" {
+            return Ok(Input::ReleaseAll);
+        }
+        if input == "shoot" {
+            return Ok(Input::Shoot);
+        }
+        if input == "weapon" {
+            return Ok(Input::CycleWeapon);
+        }
+        let movement = |name: &str| {
+            name.parse::<Action>()
+                .ok()
+                .filter(|action| action.is_movement())
+                .ok_or_else(|| format!("unknown input '{}'", input))
+        };
+        if let Some(name) = input.strip_prefix('+') {
+            return movement(name).map(Input::Press);
+        }
+        if let Some(name) = input.strip_prefix('-') {
+            return movement(name).map(Input::Release);
+        }
+        input
+            .strip_prefix('@')
+            .and_then(|column| column.parse().ok())
+            .map(Input::Aim)
+            .ok_or_else(|| format!("unknown input '{}'", input))
+    }
+}
+
+// Provides the state of a game that advances in fixed steps, so that the same seed and inputs
+// always play out the same way, with or without a terminal.
+pub struct Game {
+    pub player: Player,
+    pub invaders: Invaders,
+    pub scorecard: Scorecard,
+    pub lives: u32,
+    pub capsules: Vec<Capsule>,
+    steering: Steering,
+    gameplay: Gameplay,
+    adaptive: Option<Adaptive>,
+    dimensions: Vec<u16>,
+    seed: u64,
+    rng: Rng,
+    ticks: u64,
+    lag: Duration,
+    inputs: Vec<(u64, Input)>,
+    sounds: Vec<Sound>,
+    retuned: bool,
+    scattered: bool,
+    over: bool,
+    won: bool,
+}
+
+// Implements the game.
+impl Game {
+
+    // Creates a new game within the given dimensions; continuous steering requires key-release
+    // events from the terminal.
+    pub fn new(
+        dimensions: &[u16],
+        gameplay: &Gameplay,
+        adaptive: bool,
+        continuous: bool,
+        seed: u64,
+    ) -> Self {
+        let mut player = Player::new(dimensions);
+        player.tune(gameplay);
+        let mut invaders = Invaders::with_gameplay(dimensions, gameplay);
+        invaders.reseed(seed);
+        let mut steering = Steering::new(continuous);
+        steering.tune(gameplay);
+        Self {
+            player,
+            invaders,
+            scorecard: Scorecard::new(),
+            lives: gameplay.lives,
+            capsules: Vec::new(),
+            steering,
+            gameplay: *gameplay,
+            adaptive: adaptive.then(Adaptive::new),
+            dimensions: dimensions.to_vec(),
+            seed,
+
+            // Draws power-ups from a sequence of its own, apart from the army's bombs.
+            rng: Rng::new(!seed),
+            ticks: 0,
+            lag: Duration::ZERO,
+            inputs: Vec::new(),
+            sounds: Vec::new(),
+            retuned: false,
+            scattered: false,
+            over: false,
+            won: false,
+        }
+    }
+
+    // Leaves random gaps in this and every later army, e.g., for a daily challenge with a new
+    // formation every day.
+    pub fn scatter_armies(&mut self) {
+        self.scattered = true;
+        self.invaders.scatter();
+    }
+
+    // Determines whether the armies have gaps.
+    pub fn scattered(&self) -> bool {
+        self.scattered
+    }
+
+    // Returns the seed the game was started with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // Returns the dimensions of the playfield.
+    pub fn dimensions(&self) -> &[u16] {
+        &self.dimensions
+    }
+
+    // Returns the current tuning, without the adaptive difficulty's nudges.
+    pub fn gameplay(&self) -> Gameplay {
+        self.gameplay
+    }
+
+    // Returns the number of steps played so far.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    // Returns the time played so far.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_millis(self.ticks * STEP_MILLIS)
+    }
+
+    // Returns all inputs so far with the step they were made before.
+    pub fn inputs(&self) -> &[(u64, Input)] {
+        &self.inputs
+    }
+
+    // Determines whether the tuning changed during the game, which a replay cannot repeat.
+    pub fn retuned(&self) -> bool {
+        self.retuned
+    }
+
+    // Determines whether the game is over, won or lost.
+    pub fn over(&self) -> bool {
+        self.over
+    }
+
+    // Determines whether the last wave was cleared.
+    pub fn won(&self) -> bool {
+        self.won
+    }
+
+    // Takes the sounds the game made since it was last asked.
+    pub fn take_sounds(&mut self) -> Vec<Sound> {
+        std::mem::take(&mut self.sounds)
+    }
+
+    // Applies a changed tuning, e.g., from a reloaded configuration.
+    pub fn tune(&mut self, gameplay: &Gameplay, adaptive: bool) {
+        if *gameplay != self.gameplay || adaptive != self.adaptive.is_some() {
+            self.retuned = true;
+        }
+        self.gameplay = *gameplay;
+        if !adaptive {
+            self.adaptive = None;
+        } else if self.adaptive.is_none() {
+            self.adaptive = Some(Adaptive::new());
+        }
+        self.player.tune(gameplay);
+        self.invaders.tune(&self.adapted());
+        self.steering.tune(gameplay);
+    }
+
+    // Handles an input right away and logs it.
+    pub fn input(&mut self, input: Input) {
+        if self.over {
+            return;
+        }
+        self.inputs.push((self.ticks, input));
+        match input {
+            Input::Press(action) => self.steering.press(action, &mut self.player),
+            Input::Release(action) => self.steering.release(action),
+            Input::ReleaseAll => self.steering.release_all(),
+            Input::Aim(column) => self.steering.aim(column),
+            Input::CycleWeapon => self.player.arsenal.cycle(),
+            Input::Shoot => {
+                let fired = self.player.shoot();
+                if fired > 0 {
+                    self.sounds.push(Sound::Shoot);
+                }
+                for _ in 0..fired {
+                    self.scorecard.record_shot();
+                    if let Some(adaptive) = self.adaptive.as_mut() {
+                        adaptive.record_shot();
+                    }
+                }
+            }
+        }
+    }
+
+    // Determines whether the ship keeps moving while a key is held.
+    pub fn continuous(&self) -> bool {
+        self.steering.continuous()
+    }
+
+    // Determines whether the adaptive difficulty is enabled.
+    pub fn adaptive(&self) -> bool {
+        self.adaptive.is_some()
+    }
+
+    // Plays as many steps as fit into the time that passed; keeps the rest for later.
+    pub fn advance(&mut self, delta: Duration) {
+        let step = Duration::from_millis(STEP_MILLIS);
+        self.lag += delta;
+        while self.lag >= step && !self.over {
+            self.lag -= step;
+            self.step();
+        }
+    }
+
+    // Plays a single step.
+    pub fn step(&mut self) {
+        if self.over {
+            return;
+        }
+        let delta = Duration::from_millis(STEP_MILLIS);
+        self.ticks += 1;
+
+        // Updates player and invader positions; the army and its bombs move at half the speed
+        // while time is slowed down.
+        self.steering.update(delta, &mut self.player);
+        self.player.update(delta);
+        let army_delta = if self.player.powers.active(Power::SlowTime) {
+            delta / 2
+        } else {
+            delta
+        };
+        if self.invaders.update(army_delta) {
+            self.sounds.push(Sound::March(self.invaders.beat()));
+        }
+
+        // Nudges the march to the player's recent accuracy.
+        if let Some(adaptive) = self.adaptive.as_mut() {
+            if adaptive.update(delta) {
+                self.invaders.tune(&adaptive.apply(&self.gameplay));
+            }
+        }
+
+        // Detects shots hitting invaders, which now and then drop a power-up.
+        let hits = self.player.detect_hit(&mut self.invaders);
+        for _ in 0..hits.shots {
+            self.scorecard.record_hit();
+            if let Some(adaptive) = self.adaptive.as_mut() {
+                adaptive.record_hit();
+            }
+        }
+        if !hits.kills.is_empty() {
+            self.sounds.push(Sound::Explode);
+        }
+        for (x, y) in hits.kills {
+            self.scorecard.record_kill();
+            if self.rng.below(POWER_UP_CHANCE) == 0 {
+                let power = Power::ALL[self.rng.below(Power::ALL.len() as u64) as usize];
+                self.capsules.push(Capsule::new(x, y, power));
+            }
+        }
+
+        // Lets capsules fall, and grants the power or weapon of those the ship catches.
+        let height = self.dimensions[1];
+        self.capsules
+            .retain_mut(|capsule| capsule.update(army_delta, height));
+        let (player_x, player_y) = self.player.position();
+        let caught: Vec<Power> = self
+            .capsules
+            .iter()
+            .filter(|capsule| (capsule.x, capsule.y) == (player_x, player_y))
+            .map(|capsule| capsule.power)
+            .collect();
+        self.capsules
+            .retain(|capsule| (capsule.x, capsule.y) != (player_x, player_y));
+        for power in caught {
+            self.sounds.push(Sound::PowerUp);
+            match power {
+                Power::ExtraLife => self.lives += 1,
+                power => self.player.power_up(power),
+            }
+        }
+
+        // Detects bombs hitting the ship, which costs a life unless a shield takes the hit, and
+        // clears the sky.
+        if self.invaders.bomb_hit(player_x, player_y) {
+            self.invaders.clear_bombs();
+            if self.player.powers.consume(Power::Shield) {
+                self.sounds.push(Sound::Explode);
+            } else {
+                self.lives -= 1;
+                self.sounds.push(Sound::PlayerDeath);
+                if let Some(adaptive) = self.adaptive.as_mut() {
+                    adaptive.record_death();
+                    self.invaders.tune(&adaptive.apply(&self.gameplay));
+                }
+            }
+        }
+
+        // Winning condition: if the invaders of the last wave are all dead.
+        if self.invaders.all_killed() && self.scorecard.wave == WAVES {
+            self.sounds.push(Sound::WaveClear);
+            self.won = true;
+            self.over = true;
+            return;
+        }
+
+        // Sends in the next, faster wave once the invaders are all dead.
+        if self.invaders.all_killed() {
+            self.sounds.push(Sound::WaveClear);
+            self.scorecard.next_wave();
+            let wave = u64::from(self.scorecard.wave);
+            self.invaders = Invaders::with_gameplay(&self.dimensions, &self.adapted());
+            self.invaders.speed_up(wave - 1);
+            self.invaders.reseed(self.seed.wrapping_add(wave));
+            if self.scattered {
+                self.invaders.scatter();
+            }
+        }
+
+        // Losing conditions: if invaders reached the bottom, or bombs took all lives.
+        if self.invaders.reached_bottom() {
+            self.sounds.push(Sound::PlayerDeath);
+            self.over = true;
+        }
+        if self.lives == 0 {
+            self.over = true;
+        }
+    }
+
+    // Returns the tuning with the march nudged by the adaptive difficulty, if enabled.
+    fn adapted(&self) -> Gameplay {
+        self.adaptive
+            .as_ref()
+            .map_or(self.gameplay, |adaptive| adaptive.apply(&self.gameplay))
+    }
+}
+
+// Implements the drawable trait for the game.
+impl Drawable for Game {
+
+    // Draws the player, all remaining invaders, and falling capsules.
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        self.player.draw(frame, theme);
+        self.invaders.draw(frame, theme);
+        for capsule in self.capsules.iter() {
+            capsule.draw(frame, theme);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    config::Theme,
+    frame::{self, Drawable, Frame},
+    leaderboard::{self, Entry},
+    score::Scorecard,
+};
+
+// Provides the longest name that can be entered for the leaderboard.
+const NAME_LENGTH: usize = 8;
+
+// Provides a game-over screen that asks for a name to put on the leaderboard.
+pub struct GameOver {
+    scorecard: Scorecard,
+    table: Vec<Entry>,
+    name: String,
+    done: bool,
+    saved: bool,
+}
+
+// Implements the game-over screen.
+impl GameOver {
+
+    // Creates a new game-over screen for a score that makes it into the given table.
+    pub fn new(scorecard: Scorecard, table: Vec<Entry>, name: &str) -> Self {
+        Self {
+            scorecard,
+            table,
+
+            // Suggests a name, e.g., the user's login.
+            name: name
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric())
+                .map(|c| c.to_ascii_uppercase())
+                .take(NAME_LENGTH)
+                .collect(),
+            done: false,
+            saved: false,
+        }
+    }
+
+    // Handles a key press; letters and digits make up the name, enter saves it, and escape
+    // skips the leaderboard.
+    pub fn input(&mut self, key: &KeyEvent) {
+        if key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return;
+        }
+        match key.code {
+            KeyCode::Char(c) if c.is_ascii_alphanumeric() && self.name.len() < NAME_LENGTH => {
+                self.name.push(c.to_ascii_uppercase())
+            }
+            KeyCode::Backspace => {
+                self.name.pop();
+            }
+            KeyCode::Enter if !self.name.is_empty() => {
+                self.saved = true;
+                self.done = true;
+            }
+            KeyCode::Esc => self.done = true,
+            _ => {}
+        }
+    }
+
+    // Determines whether the player is done with the screen.
+    pub fn done(&self) -> bool {
+        self.done
+    }
+
+    // Returns the entered name once the player chose to save it.
+    pub fn name(&self) -> Option<&str> {
+        self.saved.then_some(self.name.as_str())
+    }
+
+    // Lists the lines shown on the screen.
+    fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            "GAME OVER".to_string(),
+            String::new(),
+            format!(
+                "score {}  wave {}  accuracy {}%",
+                self.scorecard.score,
+                self.scorecard.wave,
+                self.scorecard.accuracy()
+            ),
+            String::new(),
+            format!(
+                "New high score! Your name: {:_<width$}",
+                self.name,
+                width = NAME_LENGTH
+            ),
+            "enter to save, esc to skip".to_string(),
+        ];
+        if !self.table.is_empty() {
+            lines.push(String::new());
+            lines.extend(leaderboard::format_table(&self.table));
+        }
+        lines
+    }
+}
+
+// Implements the drawable trait for the game-over screen.
+impl Drawable for GameOver {
+
+    // Draws the game-over screen centered on a blank box.
+    fn draw(&self, frame: &mut Frame, _theme: &Theme) {
+        frame::draw_box(frame, &self.lines());
+    }
+}
@@ -0,0 +1,231 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::backtrace::Backtrace;
+use std::fs;
+use std::io::{self, Write};
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crossterm::cursor::{Hide, Show};
+use crossterm::event::{
+    DisableFocusChange, DisableMouseCapture, EnableFocusChange, KeyboardEnhancementFlags,
+    PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::ExecutableCommand;
+
+// Remembers whether the terminal is currently taken over, and whether key releases were asked for.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static ENHANCED_KEYS: AtomicBool = AtomicBool::new(false);
+
+// Provides the requests that signals leave for the game loop.
+#[derive(Default)]
+struct Requests {
+    quit: AtomicI32,
+    suspend: AtomicBool,
+    resumed: AtomicBool,
+}
+
+// Provides a guard that owns the terminal while the game runs and gives it back when dropped.
+pub struct TerminalGuard {
+    enhanced_keys: bool,
+    requests: Arc<Requests>,
+}
+
+// Implements the terminal guard.
+impl TerminalGuard {
+
+    // Switches the terminal to raw mode on the alternate screen with a hidden cursor, and makes
+    // sure it is restored on panics and on SIGINT, SIGTERM, and SIGHUP.
+    pub fn enter(enhanced_keys: bool) -> io::Result<Self> {
+        install_panic_hook();
+        let requests = Arc::new(Requests::default());
+        watch_signals(Arc::clone(&requests))?;
+        let guard = Self {
+            enhanced_keys,
+            requests,
+        };
+        guard.take_over()?;
+        Ok(guard)
+    }
+
+    // Takes over the terminal; leaves settings alone that are still in place.
+    fn take_over(&self) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        ACTIVE.store(true, Ordering::SeqCst);
+        terminal::enable_raw_mode()?;
+        stdout.execute(EnterAlternateScreen)?;
+        stdout.execute(Hide)?;
+        stdout.execute(EnableFocusChange)?;
+
+        // Asks for key-release events to steer smoothly.
+        if self.enhanced_keys && !ENHANCED_KEYS.swap(true, Ordering::SeqCst) {
+            stdout.execute(PushKeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                    | KeyboardEnhancementFlags::REPORT_EVENT_TYPES,
+            ))?;
+        }
+        Ok(())
+    }
+
+    // Returns the signal that asked the game to quit, if any.
+    pub fn signal(&self) -> Option<i32> {
+        match self.requests.quit.load(Ordering::SeqCst) {
+            0 => None,
+            signal => Some(signal),
+        }
+    }
+
+    // Determines whether SIGTSTP asked the game to suspend; asks only once per signal.
+    pub fn suspend_requested(&self) -> bool {
+        self.requests.suspend.swap(false, Ordering::SeqCst)
+    }
+
+    // Gives the terminal back to the shell and stops the process until it is continued, like
+    // Ctrl-Z does outside of raw mode; the game is told to redraw once resumed.
+    #[cfg(unix)]
+    pub fn suspend(&self) -> io::Result<()> {
+        restore_terminal();
+        signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)?;
+        self.requests.resumed.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    // Keeps running where processes cannot be suspended.
+    #[cfg(not(unix))]
+    pub fn suspend(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    // Takes the terminal over again after the process was continued; returns whether it was,
+    // so that the game can redraw everything.
+    pub fn resume(&self) -> io::Result<bool> {
+        if !self.requests.resumed.swap(false, Ordering::SeqCst) {
+            return Ok(false);
+        }
+        self.take_over()?;
+        Ok(true)
+    }
+}
+
+// Implements restoring the terminal once the guard goes out of scope, also while unwinding.
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+// Gives the terminal back to the shell; does nothing if it was restored already.
+pub fn restore_terminal() {
+    restore_terminal_to(&mut io::stdout());
+}
+
+// Gives the terminal back by writing the escape codes to the given output, e.g., the terminal.
+pub fn restore_terminal_to(out: &mut impl Write) {
+    if !ACTIVE.swap(false, Ordering::SeqCst) {
+        return;
+    }
+
+    // Keeps going on errors, e.g., when the terminal hung up, to restore as much as possible.
+    if ENHANCED_KEYS.swap(false, Ordering::SeqCst) {
+        let _ = out.execute(PopKeyboardEnhancementFlags);
+    }
+    let _ = out.execute(DisableMouseCapture);
+    let _ = out.execute(DisableFocusChange);
+    let _ = out.execute(Show);
+    let _ = out.execute(LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+}
+
+// Restores the terminal before the panic message is printed, and saves a crash report.
+fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+        let report = crash_report(info, &Backtrace::force_capture().to_string());
+        match save_crash_report(&std::env::temp_dir(), &report) {
+            Ok(path) => eprintln!("A crash report was saved to {}", path.display()),
+            Err(error) => eprintln!("Failed to save a crash report: {}", error),
+        }
+    }));
+}
+
+// Describes a panic for a crash report.
+fn crash_report(info: &PanicHookInfo, backtrace: &str) -> String {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    let location = info
+        .location()
+        .map(|location| location.to_string())
+        .unwrap_or_else(|| "unknown location".to_string());
+    format!(
+        "terminal_invaders {} crashed\nthread: {}\nmessage: {}\nlocation: {}\n\n{}",
+        env!("CARGO_PKG_VERSION"),
+        std::thread::current().name().unwrap_or("unnamed"),
+        message,
+        location,
+        backtrace
+    )
+}
+
+// Saves a crash report into the given directory; returns the path of the report.
+pub fn save_crash_report(dir: &Path, report: &str) -> io::Result<PathBuf> {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_millis());
+    let path = dir.join(format!("terminal_invaders-crash-{}.txt", millis));
+    fs::File::create(&path)?.write_all(report.as_bytes())?;
+    Ok(path)
+}
+
+// Asks the game to quit on the first SIGINT, SIGTERM, or SIGHUP, and quits right away on the
+// second, in case the game does not respond; asks it to suspend on SIGTSTP and to take the
+// terminal over again on SIGCONT.
+#[cfg(unix)]
+fn watch_signals(requests: Arc<Requests>) -> io::Result<()> {
+    use signal_hook::consts::{SIGCONT, SIGHUP, SIGINT, SIGTERM, SIGTSTP};
+    use signal_hook::iterator::Signals;
+
+    let mut signals = Signals::new([SIGINT, SIGTERM, SIGHUP, SIGTSTP, SIGCONT])?;
+    std::thread::spawn(move || {
+        for received in signals.forever() {
+            match received {
+                SIGTSTP => requests.suspend.store(true, Ordering::SeqCst),
+                SIGCONT => requests.resumed.store(true, Ordering::SeqCst),
+                _ => {
+                    if requests.quit.swap(received, Ordering::SeqCst) != 0 {
+                        restore_terminal();
+                        std::process::exit(128 + received);
+                    }
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+// Leaves Ctrl-C to the console elsewhere, which crossterm reports as a key.
+#[cfg(not(unix))]
+fn watch_signals(_requests: Arc<Requests>) -> io::Result<()> {
+    Ok(())
+}
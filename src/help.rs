@@ -0,0 +1,56 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    config::Theme,
+    frame::{self, Drawable, Frame},
+    keys::KeyMap,
+};
+
+// Provides a help screen listing the key bindings.
+pub struct Help {
+    pub visible: bool,
+    lines: Vec<String>,
+}
+
+// Implements the help screen.
+impl Help {
+
+    // Creates a new hidden help screen for the given key bindings.
+    pub fn new(keys: &KeyMap) -> Self {
+        let mut lines = vec!["CONTROLS".to_string(), String::new()];
+        lines.extend(keys.describe());
+        Self {
+            visible: false,
+            lines,
+        }
+    }
+
+    // Shows or hides the help screen.
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+}
+
+// Implements the drawable trait for the help screen.
+impl Drawable for Help {
+
+    // Draws the help screen centered on a blank box.
+    fn draw(&self, frame: &mut Frame, _theme: &Theme) {
+        if !self.visible {
+            return;
+        }
+        frame::draw_box(frame, &self.lines);
+    }
+}
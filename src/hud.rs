@@ -0,0 +1,176 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use rusty_time::prelude::Timer;
+
+use crate::{
+    config::Theme,
+    frame::{Drawable, Frame},
+    powerup::Power,
+    weapon::Weapon,
+    MARCH_BEATS,
+};
+
+// Provides a heads-up display drawn on top of the game.
+pub struct Hud {
+    bounds: Vec<u16>,
+    metronome: Option<usize>,
+    toast: Option<(String, Timer)>,
+    lives: u32,
+    score: Option<(u64, u32)>,
+    powers: String,
+    weapon: String,
+    paused: bool,
+}
+
+// Implements the heads-up display.
+impl Hud {
+
+    // Creates a new heads-up display within the given dimensions.
+    pub fn new(dimensions: &[u16]) -> Self {
+        Self {
+            bounds: dimensions.to_vec(),
+
+            // The metronome stays hidden until there is a beat to show.
+            metronome: None,
+
+            // There is nothing to tell, yet.
+            toast: None,
+
+            // The lives stay hidden until the game sets them.
+            lives: 0,
+            score: None,
+            powers: String::new(),
+            weapon: String::new(),
+            paused: false,
+        }
+    }
+
+    // Shows the score and the wave.
+    pub fn set_score(&mut self, score: u64, wave: u32) {
+        self.score = Some((score, wave));
+    }
+
+    // Shows the active power-ups with the seconds they have left, e.g., `rapid 7s`.
+    pub fn set_powers(&mut self, powers: &[(Power, Duration)]) {
+        let powers: Vec<String> = powers
+            .iter()
+            .map(|(power, left)| format!("{} {}s", power.name(), left.as_millis().div_ceil(1000)))
+            .collect();
+        self.powers = powers.join("  ");
+    }
+
+    // Shows the selected weapon with the volleys it has left, e.g., `spread 24`, or hides it
+    // while there is nothing to choose from.
+    pub fn set_weapon(&mut self, weapon: Option<(Weapon, Option<u32>)>) {
+        self.weapon = match weapon {
+            Some((weapon, Some(ammo))) => format!("{} {}", weapon.name(), ammo),
+            Some((weapon, None)) => weapon.name().to_string(),
+            None => String::new(),
+        };
+    }
+
+    // Shows or hides a notice that the game is paused.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    // Shows the remaining lives of the ship.
+    pub fn set_lives(&mut self, lives: u32) {
+        self.lives = lives;
+    }
+
+    // Shows a short message for a few seconds.
+    pub fn toast(&mut self, message: &str) {
+        self.toast = Some((message.to_string(), Timer::from_millis(4000)));
+    }
+
+    // Updates the timers of the heads-up display.
+    pub fn update(&mut self, delta: Duration) {
+        if let Some((_, timer)) = self.toast.as_mut() {
+            timer.update(delta);
+            if timer.ready {
+                self.toast = None;
+            }
+        }
+    }
+
+    // Shows the given march beat as a visual metronome, or hides it.
+    pub fn set_metronome(&mut self, beat: Option<usize>) {
+        self.metronome = beat;
+    }
+}
+
+// Implements the drawable trait for the heads-up display.
+impl Drawable for Hud {
+
+    // Draws the heads-up display on a given frame.
+    fn draw(&self, frame: &mut Frame, _theme: &Theme) {
+
+        // Draws the remaining lives in the top-left corner, followed by the score.
+        for col in frame.iter_mut().take(self.lives as usize) {
+            col[0] = "♥".to_string();
+        }
+        if let Some((score, wave)) = self.score {
+            let text = format!("{}  wave {}", score, wave);
+            let left = self.lives as usize + 1;
+            let width = (self.bounds[0] as usize).saturating_sub(MARCH_BEATS + 1);
+            for (col, c) in frame.iter_mut().take(width).skip(left).zip(text.chars()) {
+                col[0] = c.to_string();
+            }
+        }
+
+        // Draws the weapon and the power-ups right-aligned next to the metronome.
+        let text: Vec<&str> = [self.weapon.as_str(), self.powers.as_str()]
+            .into_iter()
+            .filter(|text| !text.is_empty())
+            .collect();
+        let text = text.join("  ");
+        let right = (self.bounds[0] as usize).saturating_sub(MARCH_BEATS + 1);
+        let left = right.saturating_sub(text.chars().count());
+        for (col, c) in frame.iter_mut().take(right).skip(left).zip(text.chars()) {
+            col[0] = c.to_string();
+        }
+
+        // Draws the toast over the lives, leaving room for the metronome.
+        if let Some((message, _)) = &self.toast {
+            let width = (self.bounds[0] as usize).saturating_sub(MARCH_BEATS + 1);
+            for (col, c) in frame.iter_mut().zip(message.chars()).take(width) {
+                col[0] = c.to_string();
+            }
+        }
+
+        // Draws the pause notice in the center.
+        if self.paused {
+            let notice = "PAUSED";
+            let left = (self.bounds[0] as usize).saturating_sub(notice.len()) / 2;
+            let row = self.bounds[1] as usize / 2;
+            for (col, c) in frame.iter_mut().skip(left).zip(notice.chars()) {
+                if let Some(cell) = col.get_mut(row) {
+                    *cell = c.to_string();
+                }
+            }
+        }
+
+        // Draws the metronome in the top-right corner, highlighting the current beat.
+        if let Some(beat) = self.metronome {
+            let left = (self.bounds[0] as usize).saturating_sub(MARCH_BEATS);
+            for (offset, col) in frame.iter_mut().skip(left).take(MARCH_BEATS).enumerate() {
+                col[0] = if offset == beat { "●" } else { "○" }.to_string();
+            }
+        }
+    }
+}
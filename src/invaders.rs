@@ -16,118 +16,373 @@ use std::{cmp::max, time::Duration};
 
 use rusty_time::prelude::Timer;
 
-use crate::frame::{Drawable, Frame};
+use crate::{
+    bomb::Bomb,
+    config::{Gameplay, Theme},
+    frame::{Drawable, Frame},
+    rng::Rng,
+    MARCH_BEATS,
+};
 
-// Provides an invader struct with coordinates.
+// Provides an invader struct with coordinates, where it stood before the last step, and the hits
+// it can take.
 pub struct Invader {
     x: u16,
     y: u16,
+    from: (u16, u16),
+    health: u32,
 }
 
-// Provides an invaders struct for an entire army.
+// Provides an invaders struct for an entire army, indexed by position.
 pub struct Invaders {
-    pub army: Vec<Invader>,
+    army: Vec<Invader>,
+    grid: Vec<Option<usize>>,
+    alive: usize,
+    stepped: bool,
     move_timer: Timer,
     direction: i32,
     bounds: Vec<u16>,
+    beat: usize,
+    descents: u64,
+    gameplay: Gameplay,
+    bombs: Vec<Bomb>,
+    bomb_timer: Option<Timer>,
+    rng: Rng,
 }
 
 // Implements the invaders army.
 impl Invaders {
 
     // Creates a new invaders army within the given dimensions.
-    pub fn new(dimensions: &Vec<u16>) -> Self {
+    pub fn new(dimensions: &[u16]) -> Self {
+        Self::with_gameplay(dimensions, &Gameplay::default())
+    }
+
+    // Creates a new invaders army within the given dimensions, as dense as the tuning asks for.
+    pub fn with_gameplay(dimensions: &[u16], gameplay: &Gameplay) -> Self {
 
         // Creates the army.
         let mut army = Vec::new();
         for x in 0..dimensions[0] {
             for y in 0..dimensions[1] {
                 if (x > 1)
-                    && (x < dimensions[0] - 2)
+                    && (x < dimensions[0].saturating_sub(2))
                     && (y > 0)
-                    && (y < dimensions[1] / 2 - 2)
-                    && (x % 2 == 0)
+                    && (y < (dimensions[1] / 2).saturating_sub(2))
+                    && (x % gameplay.army_spacing == 0)
                     && (y % 2 == 0)
                 {
-                    army.push(Invader { x, y });
+                    army.push(Invader {
+                        x,
+                        y,
+                        from: (x, y),
+                        health: 1,
+                    });
                 }
             }
         }
-        Self {
+        let mut invaders = Self {
+
+            // The army keeps its formation order, and killed invaders leave a gap in it until
+            // the next step; every cell points at the invader standing there, if any.
+            alive: army.len(),
             army,
+            grid: vec![None; dimensions[0] as usize * dimensions[1] as usize],
+            stepped: false,
 
-            // The army changes position every 2 seconds.
-            move_timer: Timer::from_millis(2000),
+            // The army changes position every 2 seconds by default.
+            move_timer: Timer::from_millis(gameplay.march_millis),
 
             // The army moves in different directions.
             direction: 1,
 
             // The army's boundaries.
-            bounds: dimensions.clone(),
+            bounds: dimensions.to_vec(),
+
+            // The army marches to a cycling beat.
+            beat: MARCH_BEATS - 1,
+
+            // The army speeds up every time it moves downwards.
+            descents: 0,
+            gameplay: *gameplay,
+
+            // The army drops bombs at random, unless the tuning disables them.
+            bombs: Vec::new(),
+            bomb_timer: None,
+            rng: Rng::new(0),
+        };
+        invaders.bomb_timer = invaders.next_bomb_timer();
+        invaders.index();
+        invaders
+    }
+
+    // Reseeds the random choices of the army, e.g., when and where bombs drop.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+        self.bomb_timer = self.next_bomb_timer();
+    }
+
+    // Leaves random gaps in the army, dropping about every fourth column but never all of them.
+    pub fn scatter(&mut self) {
+        let mut columns: Vec<u16> = self.army.iter().map(|invader| invader.x).collect();
+        columns.dedup();
+        let dropped: Vec<u16> = columns
+            .iter()
+            .copied()
+            .filter(|_| self.rng.below(4) == 0)
+            .collect();
+        if dropped.len() < columns.len() {
+            self.unindex();
+            self.army.retain(|invader| !dropped.contains(&invader.x));
+            self.alive = self.army.len();
+            self.index();
         }
     }
 
-    // Updates the army positions.
-    pub fn update(&mut self, delta: Duration) {
+    // Returns the number of invaders still alive.
+    pub fn alive(&self) -> usize {
+        self.alive
+    }
+
+    // Returns the invaders still alive, in formation order.
+    fn living(&self) -> impl Iterator<Item = &Invader> {
+        self.army.iter().filter(|invader| invader.health > 0)
+    }
+
+    // Returns the cell of the grid at the given position, or none if it is out of bounds.
+    fn cell(&self, x: u16, y: u16) -> Option<usize> {
+        let (width, height) = (self.bounds[0], self.bounds[1]);
+        (x < width && y < height).then(|| y as usize * width as usize + x as usize)
+    }
+
+    // Returns the invader still alive at the given position.
+    fn at(&self, x: u16, y: u16) -> Option<usize> {
+        self.cell(x, y).and_then(|cell| self.grid[cell])
+    }
+
+    // Removes the invaders from the grid, e.g., before they move.
+    fn unindex(&mut self) {
+        for idx in 0..self.army.len() {
+            let invader = &self.army[idx];
+            if let Some(cell) = self.cell(invader.x, invader.y) {
+                self.grid[cell] = None;
+            }
+        }
+    }
+
+    // Closes the gaps killed invaders left in the army, and puts those alive on the grid.
+    fn index(&mut self) {
+        self.army.retain(|invader| invader.health > 0);
+        for idx in 0..self.army.len() {
+            let invader = &self.army[idx];
+            if let Some(cell) = self.cell(invader.x, invader.y) {
+                self.grid[cell] = Some(idx);
+            }
+        }
+    }
+
+    // Applies the tuning to the army's march, keeping the progress of the current step.
+    pub fn tune(&mut self, gameplay: &Gameplay) {
+        let bomb_millis = self.gameplay.bomb_millis;
+        self.gameplay = *gameplay;
+        if gameplay.bomb_millis != bomb_millis {
+            self.bomb_timer = self.next_bomb_timer();
+        }
+        let progress =
+            self.move_timer.time_left.as_secs_f64() / self.move_timer.duration.as_secs_f64();
+        self.move_timer = Timer::from_millis(self.march_millis());
+        self.move_timer.time_left = self.move_timer.duration.mul_f64(progress.clamp(0.0, 1.0));
+    }
+
+    // Speeds the march up as if the army had descended the given number of times, e.g., for
+    // later waves.
+    pub fn speed_up(&mut self, descents: u64) {
+        self.descents += descents;
+        self.move_timer = Timer::from_millis(self.march_millis());
+    }
+
+    // Returns the time between two steps, which shrinks with every descent.
+    fn march_millis(&self) -> u64 {
+        let gameplay = &self.gameplay;
+        let speedup = gameplay.march_speedup_millis.saturating_mul(self.descents);
+        max(
+            gameplay.march_millis.saturating_sub(speedup),
+            gameplay.min_march_millis,
+        )
+    }
+
+    // Returns the timer until the next bomb drops, randomly between half and one and a half
+    // times the average; returns none if bombs are disabled.
+    fn next_bomb_timer(&mut self) -> Option<Timer> {
+        let average = self.gameplay.bomb_millis;
+        if average == 0 {
+            return None;
+        }
+        let millis = average / 2 + self.rng.below(average + 1);
+        Some(Timer::from_millis(millis))
+    }
+
+    // Lets the lowest invader of a random column drop a bomb.
+    fn drop_bomb(&mut self) {
+        if self.alive == 0 {
+            return;
+        }
+        let nth = self.rng.below(self.alive as u64) as usize;
+        let column = self.living().nth(nth).map_or(0, |invader| invader.x);
+        let lowest = self
+            .living()
+            .filter(|invader| invader.x == column)
+            .map(|invader| invader.y)
+            .max()
+            .unwrap_or(0);
+        if lowest + 1 < self.bounds[1] {
+            self.bombs.push(Bomb::new(column, lowest + 1));
+        }
+    }
+
+    // Updates the army positions and bombs; returns whether the army took a step.
+    pub fn update(&mut self, delta: Duration) -> bool {
+        self.stepped = false;
+
+        // Drops and moves bombs, which vanish at the bottom.
+        let height = self.bounds[1];
+        self.bombs.retain_mut(|bomb| bomb.update(delta, height));
+        let mut drop = false;
+        if let Some(timer) = self.bomb_timer.as_mut() {
+            timer.update(delta);
+            drop = timer.ready;
+        }
+        if drop {
+            self.drop_bomb();
+            self.bomb_timer = self.next_bomb_timer();
+        }
+
         self.move_timer.update(delta);
 
         // Only move if timer is ready.
         if self.move_timer.ready {
             self.move_timer.reset();
+            self.beat = (self.beat + 1) % MARCH_BEATS;
             let mut downwards = false;
 
             // Determines wether it's time to move downwards.
             if self.direction == -1 {
-                let min_x = self.army.iter().map(|invader| invader.x).min().unwrap_or(0);
+                let min_x = self.living().map(|invader| invader.x).min().unwrap_or(0);
                 if min_x == 0 {
                     self.direction = 1;
                     downwards = true;
                 }
             } else {
-                let max_x = self.army.iter().map(|invader| invader.x).max().unwrap_or(0);
-                if max_x == self.bounds[0] - 1 {
+                let max_x = self.living().map(|invader| invader.x).max().unwrap_or(0);
+                if max_x >= self.bounds[0].saturating_sub(1) {
                     self.direction = -1;
                     downwards = true;
                 }
             }
 
-            // Moves downwards.
+            // Moves downwards, or sideways, and remembers where from.
+            self.unindex();
             if downwards {
-                let new_duration = max(self.move_timer.duration.as_millis() - 250, 250);
-                self.move_timer = Timer::from_millis(new_duration as u64);
-                for invader in self.army.iter_mut() {
+                self.descents += 1;
+                self.move_timer = Timer::from_millis(self.march_millis());
+            }
+            for invader in self.army.iter_mut() {
+                invader.from = (invader.x, invader.y);
+                if downwards {
                     invader.y += 1;
-                }
-            } else {
-                for invader in self.army.iter_mut() {
+                } else {
                     invader.x = ((invader.x as i32) + self.direction) as u16;
                 }
             }
+            self.index();
+            self.stepped = true;
+            return true;
         }
+        false
+    }
+
+    // Returns the beat of the last step the army took.
+    pub fn beat(&self) -> usize {
+        self.beat
     }
 
     // Determines whether all invaders were killed.
     pub fn all_killed(&self) -> bool {
-        self.army.is_empty()
+        self.alive == 0
     }
 
     // Determines whether the invaders reached the bottom.
     pub fn reached_bottom(&self) -> bool {
-        self.army.iter().map(|invader| invader.y).max().unwrap_or(0) >= self.bounds[1] - 1
+        self.living().map(|invader| invader.y).max().unwrap_or(0)
+            >= self.bounds[1].saturating_sub(1)
+    }
+
+    // Detects a bomb hitting the given position; removes the bomb if it does.
+    pub fn bomb_hit(&mut self, x: u16, y: u16) -> bool {
+        let before = self.bombs.len();
+        self.bombs.retain(|bomb| (bomb.x, bomb.y) != (x, y));
+        self.bombs.len() != before
+    }
+
+    // Removes all falling bombs, e.g., to give the player a moment after being hit.
+    pub fn clear_bombs(&mut self) {
+        self.bombs.clear();
     }
 
     // Kills an invader at the given position.
     pub fn kill_invader_at(&mut self, x: u16, y: u16) -> bool {
-        if let Some(idx) = self
-            .army
-            .iter()
-            .position(|invader| (invader.x == x) && (invader.y == y))
-        {
-            self.army.remove(idx);
-            true
-        } else {
-            false
+        self.hit_invader_at(x, y, u32::MAX) == Some(true)
+    }
+
+    // Returns the invaders in the way of a shot that flew up the column from the bottom to the
+    // top row during the last update, nearest first: those it passed or ends up at, and those
+    // that marched into it, but not those that marched out of its way.
+    pub fn in_path(&self, x: u16, top: u16, bottom: u16) -> Vec<(u16, u16)> {
+        let mut hits = Vec::new();
+
+        // Looks one row further down for an invader that descended into the shot's way.
+        for y in (top..=bottom.saturating_add(1)).rev() {
+            let Some(idx) = self.at(x, y) else {
+                continue;
+            };
+            let invader = &self.army[idx];
+            let (from_x, from_y) = if self.stepped {
+                invader.from
+            } else {
+                (invader.x, invader.y)
+            };
+
+            // One that stepped into the column only meets the shot where it went, not the row
+            // it just left.
+            let hit = if from_x != x {
+                y < bottom || y == top
+            } else {
+
+                // One that stayed in the column swept the rows in between, e.g., descending into
+                // the row the shot left while the shot rose into the invader's.
+                from_y.min(y) <= bottom
+            };
+            if hit {
+                hits.push((x, y));
+            }
+        }
+        hits
+    }
+
+    // Hits an invader at the given position with the given damage; returns whether it was
+    // killed, or none if there is no invader to hit.
+    pub fn hit_invader_at(&mut self, x: u16, y: u16, damage: u32) -> Option<bool> {
+        let idx = self.at(x, y)?;
+        let invader = &mut self.army[idx];
+        invader.health = invader.health.saturating_sub(damage);
+        if invader.health > 0 {
+            return Some(false);
         }
+        if let Some(cell) = self.cell(x, y) {
+            self.grid[cell] = None;
+        }
+        self.alive -= 1;
+        Some(true)
     }
 }
 
@@ -135,8 +390,8 @@ impl Invaders {
 impl Drawable for Invaders {
 
     // Draws the invaders on a given frame.
-    fn draw(&self, frame: &mut Frame) {
-        for invader in self.army.iter() {
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        for invader in self.living() {
 
             // Allows invaders to change appearance.
             frame[invader.x as usize][invader.y as usize] =
@@ -144,10 +399,13 @@ impl Drawable for Invaders {
                     / self.move_timer.duration.as_secs_f32())
                     > 0.5
                 {
-                    "■".to_string()
+                    theme.invader.clone()
                 } else {
-                    "□".to_string()
+                    theme.invader_alt.clone()
                 }
         }
+        for bomb in self.bombs.iter() {
+            bomb.draw(frame, theme);
+        }
     }
 }
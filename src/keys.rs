@@ -0,0 +1,390 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+// Provides all actions that can be bound to keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Shoot,
+    CycleWeapon,
+    Mouse,
+    Mute,
+    Screenshot,
+    Help,
+    Boss,
+    Suspend,
+    Quit,
+}
+
+// Implements the actions.
+impl Action {
+
+    // Lists all actions in the order they are shown on the help screen.
+    pub const ALL: [Action; 13] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::Shoot,
+        Action::CycleWeapon,
+        Action::Mouse,
+        Action::Mute,
+        Action::Screenshot,
+        Action::Help,
+        Action::Boss,
+        Action::Suspend,
+        Action::Quit,
+    ];
+
+    // Determines whether the action moves the ship.
+    pub fn is_movement(self) -> bool {
+        matches!(
+            self,
+            Action::MoveLeft | Action::MoveRight | Action::MoveUp | Action::MoveDown
+        )
+    }
+
+    // Returns the name of the action as used in the key-binding file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "left",
+            Action::MoveRight => "right",
+            Action::MoveUp => "up",
+            Action::MoveDown => "down",
+            Action::Shoot => "shoot",
+            Action::CycleWeapon => "weapon",
+            Action::Mouse => "mouse",
+            Action::Mute => "mute",
+            Action::Screenshot => "screenshot",
+            Action::Help => "help",
+            Action::Boss => "boss",
+            Action::Suspend => "suspend",
+            Action::Quit => "quit",
+        }
+    }
+}
+
+// Implements parsing actions from their names.
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Action::ALL
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+            .ok_or_else(|| format!("unknown action '{}'", name))
+    }
+}
+
+// Provides a key together with the modifiers that have to be held.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+// Implements the key binding.
+impl KeyBinding {
+
+    // Creates a new binding for the key without modifiers.
+    pub fn new(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    // Determines whether the key event triggers the binding.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+
+        // Shift is already part of the character, e.g., `A` or `?`.
+        let modifiers = match event.code {
+            KeyCode::Char(_) => event.modifiers - KeyModifiers::SHIFT,
+            _ => event.modifiers,
+        };
+        self.code == event.code && self.modifiers == modifiers
+    }
+}
+
+// Implements parsing bindings like `left`, `space`, `f1`, `q`, or `ctrl+c`.
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(binding: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts: Vec<&str> = binding.split('+').collect();
+
+        // A trailing `+` is the plus key itself, e.g., `ctrl++`.
+        let key = if binding.ends_with("++") || binding == "+" {
+            parts.truncate(parts.len().saturating_sub(2));
+            "+"
+        } else {
+            parts.pop().unwrap_or_default()
+        };
+        for modifier in parts {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{}' in '{}'", modifier, binding)),
+            };
+        }
+        let code = match key.to_lowercase().as_str() {
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "space" => KeyCode::Char(' '),
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "insert" => KeyCode::Insert,
+            "delete" => KeyCode::Delete,
+            lower => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => match lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                        Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                        _ => return Err(format!("unknown key '{}' in '{}'", key, binding)),
+                    },
+                }
+            }
+        };
+
+        // Shift is already part of a character, so `shift+a` would never match; `A` does.
+        if matches!(code, KeyCode::Char(_)) && modifiers.contains(KeyModifiers::SHIFT) {
+            return Err(format!(
+                "'{}' cannot be combined with shift, use the shifted character instead",
+                binding
+            ));
+        }
+        Ok(Self { code, modifiers })
+    }
+}
+
+// Implements displaying bindings the way they are parsed.
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl+"),
+            (KeyModifiers::ALT, "alt+"),
+            (KeyModifiers::SHIFT, "shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Left => f.write_str("left"),
+            KeyCode::Right => f.write_str("right"),
+            KeyCode::Up => f.write_str("up"),
+            KeyCode::Down => f.write_str("down"),
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Enter => f.write_str("enter"),
+            KeyCode::Esc => f.write_str("esc"),
+            KeyCode::Tab => f.write_str("tab"),
+            KeyCode::Backspace => f.write_str("backspace"),
+            KeyCode::Home => f.write_str("home"),
+            KeyCode::End => f.write_str("end"),
+            KeyCode::PageUp => f.write_str("pageup"),
+            KeyCode::PageDown => f.write_str("pagedown"),
+            KeyCode::Insert => f.write_str("insert"),
+            KeyCode::Delete => f.write_str("delete"),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+// Provides the key bindings of all actions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyMap {
+    bindings: Vec<(Action, Vec<KeyBinding>)>,
+}
+
+// Provides the names of all key-binding presets.
+pub const PRESETS: [&str; 4] = ["arrows", "wasd", "vim", "onehand"];
+
+// Implements the key map.
+impl KeyMap {
+
+    // Creates the key map of a preset; returns none for unknown presets.
+    pub fn preset(name: &str) -> Option<Self> {
+        let movement = match name {
+            "arrows" => ["left", "right", "up", "down"],
+            "wasd" => ["a", "d", "w", "s"],
+            "vim" => ["h", "l", "k", "j"],
+
+            // Everything on the numeric keypad.
+            "onehand" => ["4", "6", "8", "2"],
+            _ => return None,
+        };
+        let shoot: &[&str] = match name {
+            "onehand" => &["5", "0", "enter"],
+            _ => &["space", "enter"],
+        };
+        let cycle: &[&str] = match name {
+            "onehand" => &["tab", "."],
+            _ => &["tab"],
+        };
+        let mut map = Self {
+            bindings: Vec::new(),
+        };
+        for (action, keys) in [
+            (Action::MoveLeft, &movement[0..1]),
+            (Action::MoveRight, &movement[1..2]),
+            (Action::MoveUp, &movement[2..3]),
+            (Action::MoveDown, &movement[3..4]),
+            (Action::Shoot, shoot),
+            (Action::CycleWeapon, cycle),
+            (Action::Mouse, &["f2"]),
+            (Action::Mute, &["m"]),
+            (Action::Screenshot, &["f12"]),
+            (Action::Help, &["?", "f1"]),
+            (Action::Boss, &["b"]),
+            (Action::Suspend, &["ctrl+z"]),
+            (Action::Quit, &["q", "esc"]),
+        ] {
+            let keys = keys
+                .iter()
+                .map(|key| key.parse().expect("Preset keys should be valid."))
+                .collect();
+            map.bind(action, keys);
+        }
+        Some(map)
+    }
+
+    // Binds the action to the given keys, replacing its previous keys.
+    pub fn bind(&mut self, action: Action, keys: Vec<KeyBinding>) {
+        self.bindings.retain(|(bound, _)| *bound != action);
+        self.bindings.push((action, keys));
+        self.bindings.sort_by_key(|(bound, _)| *bound);
+    }
+
+    // Returns the keys bound to the action.
+    pub fn keys(&self, action: Action) -> &[KeyBinding] {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map_or(&[], |(_, keys)| keys.as_slice())
+    }
+
+    // Returns the action triggered by the key event, if any.
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|key| key.matches(event)))
+            .map(|(action, _)| *action)
+    }
+
+    // Finds a key bound to two actions, which would silently trigger only one of them.
+    pub fn conflict(&self) -> Option<(KeyBinding, Action, Action)> {
+        let mut claimed: Vec<(KeyBinding, Action)> = Vec::new();
+        for (action, keys) in self.bindings.iter() {
+            for key in keys {
+                if let Some((_, other)) = claimed
+                    .iter()
+                    .find(|(bound, other)| bound == key && other != action)
+                {
+                    return Some((*key, *other, *action));
+                }
+                claimed.push((*key, *action));
+            }
+        }
+        None
+    }
+
+    // Describes all bindings, one line per action, e.g., for the help screen.
+    pub fn describe(&self) -> Vec<String> {
+        Action::ALL
+            .iter()
+            .map(|action| {
+                let keys: Vec<String> = self
+                    .keys(*action)
+                    .iter()
+                    .map(|key| key.to_string())
+                    .collect();
+                format!("{:<10} {}", action.name(), keys.join(", "))
+            })
+            .collect()
+    }
+}
+
+// Implements the default key map with the arrow keys.
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self::preset("arrows").expect("The arrows preset should exist.")
+    }
+}
+
+// Provides the key bindings as configured, i.e., a preset and single actions bound to other keys.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyConfig {
+    pub preset: Option<String>,
+    pub bindings: BTreeMap<String, Vec<String>>,
+}
+
+// Implements the key-binding configuration.
+impl KeyConfig {
+
+    // Applies the bindings on top of the preset.
+    pub fn into_key_map(self) -> Result<KeyMap, Box<dyn Error>> {
+        let preset = self.preset.as_deref().unwrap_or("arrows");
+        let mut map = KeyMap::preset(preset).ok_or_else(|| {
+            format!(
+                "unknown preset '{}', expected one of {}",
+                preset,
+                PRESETS.join(", ")
+            )
+        })?;
+        for (name, keys) in self.bindings {
+            let action: Action = name.parse()?;
+            let keys = keys
+                .iter()
+                .map(|key| key.parse())
+                .collect::<Result<Vec<KeyBinding>, String>>()?;
+            map.bind(action, keys);
+        }
+        if let Some((key, first, second)) = map.conflict() {
+            return Err(format!(
+                "'{}' is bound to both {} and {}",
+                key,
+                first.name(),
+                second.name()
+            )
+            .into());
+        }
+        Ok(map)
+    }
+}
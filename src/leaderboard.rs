@@ -0,0 +1,309 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{replay::Replay, LEADERBOARD_SIZE};
+
+// Provides the version of the leaderboard file this build reads and writes.
+pub const LEADERBOARD_VERSION: u32 = 1;
+
+// Provides an entry of the leaderboard.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    pub score: u64,
+    pub wave: u32,
+    pub accuracy: u32,
+    pub date: String,
+    pub mode: String,
+    pub difficulty: String,
+
+    // Lets anyone play the run again to check the score; older entries lack it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay: Option<Replay>,
+}
+
+// Implements the leaderboard entry.
+impl Entry {
+
+    // Returns the table the entry is ranked in, as mode and difficulty.
+    pub fn table(&self) -> (&str, &str) {
+        (&self.mode, &self.difficulty)
+    }
+
+    // Orders entries from the best down: higher scores first, then later waves, then earlier
+    // dates.
+    pub fn rank(&self, other: &Entry) -> Ordering {
+        other
+            .score
+            .cmp(&self.score)
+            .then(other.wave.cmp(&self.wave))
+            .then(self.date.cmp(&other.date))
+    }
+}
+
+// Provides the contents of the leaderboard file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScoreFile {
+    version: u32,
+    #[serde(default)]
+    entries: Vec<Entry>,
+}
+
+// Provides the leaderboard stored in a file, which is safe to share between running games.
+pub struct Leaderboard {
+    path: PathBuf,
+}
+
+// Implements the leaderboard.
+impl Leaderboard {
+
+    // Creates a leaderboard stored in the given file.
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    // Returns the file the leaderboard is stored in.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Loads all entries; a missing file is an empty leaderboard.
+    pub fn load(&self) -> io::Result<Vec<Entry>> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => parse(&contents),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(error) => Err(error),
+        }
+    }
+
+    // Records the entry and returns its rank in its table, if it made it. Waits for other games
+    // writing at the same time, and moves a corrupted file aside instead of failing forever.
+    pub fn record(&self, entry: Entry) -> io::Result<Option<usize>> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let _lock = FileLock::acquire(&self.path.with_extension("toml.lock"))?;
+        let mut entries = match self.load() {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == io::ErrorKind::InvalidData => {
+                self.set_aside()?;
+                Vec::new()
+            }
+            Err(error) => return Err(error),
+        };
+        entries.push(entry.clone());
+        let entries = trim(entries);
+        let rank = rank(&entries, &entry);
+
+        // Replaces the file at once, so that readers never see half of it.
+        let contents = toml::to_string(&ScoreFile {
+            version: LEADERBOARD_VERSION,
+            entries,
+        })
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        let temporary = self
+            .path
+            .with_extension(format!("toml.{}.tmp", std::process::id()));
+        File::create(&temporary)?.write_all(contents.as_bytes())?;
+        fs::rename(&temporary, &self.path)?;
+        Ok(rank)
+    }
+
+    // Loads all entries, and moves a corrupted file aside the way recording does instead of
+    // failing; returns where the corrupted file went, if it was.
+    pub fn load_or_set_aside(&self) -> io::Result<(Vec<Entry>, Option<PathBuf>)> {
+        match self.load() {
+            Err(error) if error.kind() == io::ErrorKind::InvalidData => {}
+            loaded => return loaded.map(|entries| (entries, None)),
+        }
+
+        // Checks again under the lock, since another game may have just replaced the file.
+        let _lock = FileLock::acquire(&self.path.with_extension("toml.lock"))?;
+        match self.load() {
+            Err(error) if error.kind() == io::ErrorKind::InvalidData => {
+                Ok((Vec::new(), Some(self.set_aside()?)))
+            }
+            loaded => loaded.map(|entries| (entries, None)),
+        }
+    }
+
+    // Moves the corrupted file aside, so that the next score starts a new one; returns where it
+    // went. Expects the lock to be held.
+    fn set_aside(&self) -> io::Result<PathBuf> {
+        let corrupt = self.path.with_extension("toml.corrupt");
+        fs::rename(&self.path, &corrupt)?;
+        Ok(corrupt)
+    }
+}
+
+// Parses the contents of a leaderboard file.
+fn parse(contents: &str) -> io::Result<Vec<Entry>> {
+    let file: ScoreFile = toml::from_str(contents)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    if file.version > LEADERBOARD_VERSION {
+        return Err(io::Error::other(format!(
+            "the leaderboard was written by a newer version ({}) of the game",
+            file.version
+        )));
+    }
+    Ok(file.entries)
+}
+
+// Keeps only the best entries of every table.
+fn trim(entries: Vec<Entry>) -> Vec<Entry> {
+    tables(&entries).into_values().flatten().collect()
+}
+
+// Groups the entries by mode and difficulty, each table sorted from the best entry down and
+// limited to the size of the leaderboard.
+pub fn tables(entries: &[Entry]) -> BTreeMap<(String, String), Vec<Entry>> {
+    let mut tables: BTreeMap<(String, String), Vec<Entry>> = BTreeMap::new();
+    for entry in entries {
+        let (mode, difficulty) = entry.table();
+        tables
+            .entry((mode.to_string(), difficulty.to_string()))
+            .or_default()
+            .push(entry.clone());
+    }
+    for table in tables.values_mut() {
+        table.sort_by(Entry::rank);
+        table.truncate(LEADERBOARD_SIZE);
+    }
+    tables
+}
+
+// Merges the entries of two leaderboards, e.g., the local and the team one, leaving out
+// entries that are on both.
+pub fn merge(mut entries: Vec<Entry>, others: Vec<Entry>) -> Vec<Entry> {
+    for other in others {
+        if !entries.contains(&other) {
+            entries.push(other);
+        }
+    }
+    entries
+}
+
+// Returns the rank of the entry in its table, if it made it.
+pub fn rank(entries: &[Entry], entry: &Entry) -> Option<usize> {
+    tables(entries)
+        .get(&(entry.mode.clone(), entry.difficulty.clone()))
+        .and_then(|table| table.iter().position(|ranked| ranked == entry))
+        .map(|index| index + 1)
+}
+
+// Determines whether the entry would make it into its table.
+pub fn qualifies(entries: &[Entry], entry: &Entry) -> bool {
+    let table: Vec<&Entry> = entries
+        .iter()
+        .filter(|ranked| ranked.table() == entry.table())
+        .collect();
+    table.len() < LEADERBOARD_SIZE
+        || table
+            .iter()
+            .any(|ranked| entry.rank(ranked) == Ordering::Less)
+}
+
+// Formats a table with one line per entry, e.g., for the game-over screen.
+pub fn format_table(table: &[Entry]) -> Vec<String> {
+    table
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            format!(
+                "{:>2}. {:<8} {:>7}  wave {:>2}  {:>3}%  {}",
+                index + 1,
+                entry.name,
+                entry.score,
+                entry.wave,
+                entry.accuracy,
+                entry.date
+            )
+        })
+        .collect()
+}
+
+// Formats all tables with a heading each, e.g., for the scores command.
+pub fn format_tables(entries: &[Entry]) -> String {
+    let mut text = String::new();
+    for ((mode, difficulty), table) in tables(entries) {
+        text.push_str(&format!("{} ({})\n", mode, difficulty));
+        for line in format_table(&table) {
+            text.push_str(&format!("  {}\n", line));
+        }
+        text.push('\n');
+    }
+    if text.is_empty() {
+        text.push_str("No scores yet.\n");
+    }
+    text
+}
+
+// Returns the default location of the leaderboard, following the XDG base directories.
+pub fn default_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("terminal_invaders").join("scores.toml"))
+}
+
+// Returns the user's data directory.
+fn data_dir() -> Option<PathBuf> {
+    let non_empty = |name: &str| env::var_os(name).filter(|value| !value.is_empty());
+    if cfg!(windows) {
+        return non_empty("APPDATA").map(PathBuf::from);
+    }
+    non_empty("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| non_empty("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+}
+
+// Provides a lock on a file next to the leaderboard that keeps other games from writing at the
+// same time; the operating system releases it once the file is closed, even if the game crashed.
+struct FileLock {
+    _file: File,
+}
+
+// Implements the lock file.
+impl FileLock {
+
+    // Locks the lock file, creating it if needed, and waits up to 5 seconds for other games to
+    // finish.
+    fn acquire(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        for _ in 0..250 {
+            match file.try_lock() {
+                Ok(()) => return Ok(Self { _file: file }),
+                Err(TryLockError::WouldBlock) => thread::sleep(Duration::from_millis(20)),
+                Err(TryLockError::Error(error)) => return Err(error),
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("the leaderboard is locked by {}", path.display()),
+        ))
+    }
+}
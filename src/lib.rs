@@ -13,12 +13,96 @@
 // limitations under the License.
 
 // Provides modules for the terminal_invader crate.
+pub mod audio;
+pub mod backend;
+pub mod bomb;
+pub mod boss;
+pub mod cast;
+pub mod config;
+pub mod daily;
+pub mod date;
+pub mod difficulty;
 pub mod frame;
+pub mod game;
+pub mod game_over;
+pub mod guard;
+pub mod help;
+pub mod hud;
 pub mod invaders;
+pub mod keys;
+pub mod leaderboard;
 pub mod overlay;
+pub mod pacing;
 pub mod player;
+pub mod powerup;
 pub mod render;
+pub mod replay;
+pub mod rng;
+pub mod score;
+pub mod screenshot;
 pub mod shot;
+pub mod steering;
+pub mod synth;
+pub mod team;
+pub mod weapon;
 
 // Provides default constants for the terminal_invader application.
-pub const NUM_SHOTS: usize = 9;
+pub const NUM_SHOTS: usize = 10;
+
+// Provides the default timings of the army's march.
+pub const MARCH_MILLIS: u64 = 2000;
+pub const MARCH_SPEEDUP_MILLIS: u64 = 250;
+pub const MIN_MARCH_MILLIS: u64 = 250;
+
+// Provides the default timings of shots.
+pub const SHOT_MILLIS: u64 = 50;
+pub const EXPLOSION_MILLIS: u64 = 250;
+
+// Provides the time the blaster takes between two shots.
+pub const COOLDOWN_MILLIS: u64 = 200;
+
+// Provides the time it takes the ship to move one cell while a key is held.
+pub const MOVE_MILLIS: u64 = 40;
+
+// Provides the number of columns between two invaders of a row.
+pub const ARMY_SPACING: u16 = 2;
+
+// Provides the average time between two bombs dropped by the army, and their speed.
+pub const BOMB_MILLIS: u64 = 2000;
+pub const BOMB_FALL_MILLIS: u64 = 100;
+
+// Provides the odds of a killed invader dropping a power-up, one in this many, how fast the
+// capsule falls, and how long its power lasts.
+pub const POWER_UP_CHANCE: u64 = 8;
+pub const CAPSULE_FALL_MILLIS: u64 = 150;
+pub const POWER_UP_MILLIS: u64 = 10_000;
+
+// Provides the number of lives; every bomb that hits the ship costs one.
+pub const LIVES: u32 = 3;
+
+// Provides the number of waves; clearing the last one wins the game.
+pub const WAVES: u32 = 10;
+
+// Provides the number of entries kept in every table of the leaderboard.
+pub const LEADERBOARD_SIZE: usize = 10;
+
+// Provides the playfield of the daily challenge, the same in every terminal.
+pub const DAILY_WIDTH: u16 = 80;
+pub const DAILY_HEIGHT: u16 = 24;
+
+// Provides the number of beats the army's march cycles through.
+pub const MARCH_BEATS: usize = 4;
+
+// Provides the time the simulation advances by in every step; fixed, so that runs can be
+// played again from their inputs.
+pub const STEP_MILLIS: u64 = 5;
+
+// Provides the default number of simulation updates per second.
+pub const TICK_RATE: u32 = 200;
+
+// Provides the default number of rendered frames per second.
+pub const FRAME_RATE: u32 = 60;
+
+// Provides the rates used in low-power mode.
+pub const LOW_POWER_TICK_RATE: u32 = 60;
+pub const LOW_POWER_FRAME_RATE: u32 = 15;
@@ -12,20 +12,43 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use clap::{Arg, Command};
-use crossterm::cursor::{Hide, Show};
-use crossterm::event::{self, Event, KeyCode};
-use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
+use clap::{Arg, ArgMatches, Command};
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind, MouseButton, MouseEventKind,
+};
 use crossterm::{terminal, ExecutableCommand};
 use std::error::Error;
-use std::sync::mpsc::channel;
-use std::time::{Duration, Instant};
-use std::{io, thread};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{env, io, thread};
+use terminal_invaders::audio::{AudioSystem, Sound};
+use terminal_invaders::backend::CrosstermBackend;
+use terminal_invaders::boss::BossScreen;
+use terminal_invaders::cast::{CastRecorder, CastWriter};
+use terminal_invaders::config::{self, Config, ConfigWatcher, GameplayConfig, Theme};
+use terminal_invaders::daily::{self, DailyLog};
+use terminal_invaders::date::Date;
+use terminal_invaders::difficulty::DIFFICULTIES;
 use terminal_invaders::frame::{new_frame, Drawable, Frame};
-use terminal_invaders::invaders::Invaders;
+use terminal_invaders::game::{Game, Input};
+use terminal_invaders::game_over::GameOver;
+use terminal_invaders::guard::TerminalGuard;
+use terminal_invaders::help::Help;
+use terminal_invaders::hud::Hud;
+use terminal_invaders::keys::{Action, KeyMap, PRESETS};
+use terminal_invaders::leaderboard::{self, Entry, Leaderboard};
 use terminal_invaders::overlay::Overlay;
-use terminal_invaders::player::Player;
-use terminal_invaders::{frame, render};
+use terminal_invaders::pacing::{self, Pacer};
+use terminal_invaders::render::{RenderStats, Sent};
+use terminal_invaders::replay::{self, Rejection, Replay};
+use terminal_invaders::screenshot::Screenshot;
+use terminal_invaders::team::{self, TeamLeaderboard};
+use terminal_invaders::{frame, render, DAILY_HEIGHT, DAILY_WIDTH};
 
 // Main entry point for the terminal_invader application.
 fn main() -> Result<(), Box<dyn Error>> {
@@ -40,46 +63,278 @@ fn main() -> Result<(), Box<dyn Error>> {
                 .long("debug")
                 .help("Enable debug overlay; this might make the game unplayable."),
         )
+        .arg(
+            Arg::new("fps")
+                .long("fps")
+                .takes_value(true)
+                .value_name("FPS")
+                .validator(parse_rate)
+                .help("Limit the number of rendered frames per second [default: 60]."),
+        )
+        .arg(
+            Arg::new("tps")
+                .long("tps")
+                .takes_value(true)
+                .value_name("TPS")
+                .validator(parse_rate)
+                .help("Set the number of simulation updates per second [default: 200]."),
+        )
+        .arg(
+            Arg::new("low-power")
+                .long("low-power")
+                .help("Render at 15 fps and only on changes; saves battery and bandwidth."),
+        )
+        .arg(
+            Arg::new("mute")
+                .long("mute")
+                .help("Start with sound effects muted; toggle them in-game with m."),
+        )
+        .arg(
+            Arg::new("mouse")
+                .long("mouse")
+                .help("Steer with the mouse and click to shoot; toggle it in-game with F2."),
+        )
+        .arg(
+            Arg::new("preset")
+                .long("preset")
+                .takes_value(true)
+                .value_name("PRESET")
+                .possible_values(PRESETS)
+                .help("Choose the control scheme [default: arrows]."),
+        )
+        .arg(
+            Arg::new("difficulty")
+                .long("difficulty")
+                .takes_value(true)
+                .value_name("DIFFICULTY")
+                .possible_values(DIFFICULTIES)
+                .help("Choose the army, its bombs, and your shots and lives [default: normal]."),
+        )
+        .arg(
+            Arg::new("adaptive")
+                .long("adaptive")
+                .help("Speed up or slow down the army to match how well you play."),
+        )
+        .arg(
+            Arg::new("daily")
+                .long("daily")
+                .conflicts_with("adaptive")
+                .help("Play today's challenge, the same game for everyone; scored once a day."),
+        )
+        .arg(
+            Arg::new("config")
+                .short('c')
+                .long("config")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Load the configuration from a TOML file instead of the default location."),
+        )
+        .arg(
+            Arg::new("boss-command")
+                .long("boss-command")
+                .takes_value(true)
+                .value_name("COMMAND")
+                .help("Show the output of a command, e.g., 'cargo build', behind the boss key."),
+        )
+        .arg(
+            Arg::new("cast")
+                .long("cast")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Record the game as an asciicast v2 file, e.g., out.cast."),
+        )
+        .arg(
+            Arg::new("leaderboard-dir")
+                .long("leaderboard-dir")
+                .takes_value(true)
+                .value_name("DIR")
+                .global(true)
+                .help("Share scores with a team through a directory, e.g., on NFS."),
+        )
+        .subcommand(Command::new("scores").about("Print the leaderboard and quit."))
+        .subcommand(
+            Command::new("verify")
+                .about("Play the runs on the leaderboards again to confirm their scores.")
+                .arg(
+                    Arg::new("files")
+                        .multiple_values(true)
+                        .value_name("FILE")
+                        .help("Check these team records, leaderboard files, or directories."),
+                ),
+        )
         .get_matches();
+
+    // Prints or checks the leaderboard instead of playing if asked to.
+    if let Some(scores_args) = args.subcommand_matches("scores") {
+        return print_scores(team_leaderboard(scores_args).as_ref());
+    }
+    if let Some(verify_args) = args.subcommand_matches("verify") {
+        let files = verify_args
+            .values_of("files")
+            .map(|files| files.map(PathBuf::from));
+        return verify_scores(files.map(Iterator::collect), team_leaderboard(verify_args));
+    }
+    let team = team_leaderboard(&args);
+
     let debug_mode = args.is_present("debug");
-    let mut game_won = false;
+    let low_power = args.is_present("low-power");
+    let (default_tps, default_fps) = pacing::rates(low_power);
+    let tick_rate = args.value_of("tps").map_or(Ok(default_tps), parse_rate)?;
+    let frame_rate = args.value_of("fps").map_or(Ok(default_fps), parse_rate)?;
+
+    // Loads the configuration before the terminal switches to raw mode to report errors.
+    let mut config = match Config::load(args.value_of("config").map(Path::new)) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("Invalid configuration: {}", error);
+            process::exit(2);
+        }
+    };
+    override_config(&mut config, &args);
+    let mut keys = config.key_map()?;
+    let gameplay = config.gameplay();
+
+    // Ranks the game by its difficulty unless the tuning strays from the preset.
+    let difficulty = config.gameplay.difficulty;
+    let adaptive_mode = config.gameplay.adaptive;
+    let preset = gameplay == difficulty.gameplay();
+
+    // Watches the configuration file to apply changes while playing.
+    let mut watcher = args
+        .value_of("config")
+        .map(PathBuf::from)
+        .or_else(config::default_path)
+        .map(ConfigWatcher::new);
+
+    // Opens the sound device with synthesized sounds, or a silent sink if there is none.
+    let muted = args.is_present("mute") || config.sound.muted;
+    let mut audio = AudioSystem::open(muted, |sound| config.sound.patch(sound));
+
+    // Opens the recording before the terminal switches to raw mode.
+    let cast_file = args.value_of("cast").map(File::create).transpose()?;
+
+    // Starts the wrapped command right away, so that its output is there once the boss comes.
+    let (cols, rows) = terminal::size()?;
+    let mut boss = match args.value_of("boss-command") {
+        Some(command) => BossScreen::wrap(command, &[cols, rows])?,
+        None => BossScreen::new(&[cols, rows]),
+    };
 
-    // Creates a stdout for an alternative terminal in raw mode.
+    // Takes today's scored attempt at the daily challenge, which plays on a playfield of the same
+    // size in every terminal, if it is still open; it is used up as its game starts, so later
+    // attempts on the same day are practice, even if the first one was quit early.
+    let challenge = args.is_present("daily").then(Date::today);
+    let daily_log = challenge
+        .and_then(|_| daily::default_path())
+        .map(DailyLog::new);
+    let mut scored = true;
+    if let Some(date) = challenge {
+        let (cols, rows) = terminal::size()?;
+        if cols < DAILY_WIDTH || rows < DAILY_HEIGHT {
+            eprintln!(
+                "The daily challenge needs a terminal of at least {}x{}.",
+                DAILY_WIDTH, DAILY_HEIGHT
+            );
+            process::exit(2);
+        }
+        if let Some(log) = &daily_log {
+            scored = log.claim(date)?;
+        }
+    }
+
+    // Switches to an alternative terminal in raw mode, which is restored even on panics and
+    // signals; asks for key-release events to steer smoothly, or falls back to the keyboard's
+    // auto-repeat.
+    let enhanced_keys = matches!(terminal::supports_keyboard_enhancement(), Ok(true));
+    let guard = TerminalGuard::enter(enhanced_keys)?;
     let mut stdout = io::stdout();
-    terminal::enable_raw_mode()?;
-    stdout.execute(EnterAlternateScreen)?;
-    stdout.execute(Hide)?;
+
+    // Lets the ship follow the mouse if requested.
+    let mut mouse_mode = args.is_present("mouse");
+    if mouse_mode {
+        stdout.execute(EnableMouseCapture)?;
+    }
 
     // Gets the terminal dimensions.
     let (dim_x, dim_y) = terminal::size().expect("Terminal should have a specific size.");
-    let dimensions = vec![dim_x, dim_y];
+    let dimensions = match challenge {
+        Some(_) => vec![DAILY_WIDTH, DAILY_HEIGHT],
+        None => vec![dim_x, dim_y],
+    };
     let render_dimensions = dimensions.clone();
 
-    // Creates a render thread that can be fed with frames.
-    let (render_tx, render_rx) = channel::<Frame>();
+    // Starts recording if requested; the renderer feeds it, the game loop reports resizes.
+    let recorder = match cast_file {
+        Some(file) => Some(Arc::new(Mutex::new(CastRecorder::new(
+            BufWriter::new(file),
+            &dimensions,
+        )?))),
+        None => None,
+    };
+    let render_theme = config.theme.clone();
+    let pending_theme = Arc::new(Mutex::new(None::<Theme>));
+    let thread_theme = Arc::clone(&pending_theme);
+    let redraw = Arc::new(AtomicBool::new(false));
+    let thread_redraw = Arc::clone(&redraw);
+    let render_out: Box<dyn Write + Send> = match &recorder {
+        Some(recorder) => Box::new(CastWriter::new(io::stdout(), Arc::clone(recorder))),
+        None => Box::new(io::stdout()),
+    };
+
+    // Creates a render thread that can be fed with frames; stale frames get dropped.
+    let (render_tx, render_rx) = render::mailbox();
+    let render_stats = Arc::new(RenderStats::new());
     let render_handle = thread::spawn(move || {
         // Renders an empty frame (forced).
         let mut last_frame = frame::new_frame(&render_dimensions);
-        let mut stdout = io::stdout();
-        render::render(&mut stdout, &last_frame, &last_frame, true);
+        let mut backend = CrosstermBackend::themed(render_out, &render_theme);
+        render::render(&mut backend, &last_frame, &last_frame, true);
 
         // Launches a render loop that keeps listening for new frames.
         'renderloop: loop {
-            let curr_frame = match render_rx.recv() {
-                Ok(x) => x,
-                Err(_) => break 'renderloop,
+            let curr_frame = match render::recv_latest(&render_rx) {
+                Some(x) => x,
+                None => break 'renderloop,
             };
 
+            // Repaints everything in the colors of a reloaded theme.
+            let theme = thread_theme.lock().ok().and_then(|mut theme| theme.take());
+            if let Some(theme) = &theme {
+                backend.set_theme(theme);
+            }
+
             // Render the new frame and retain it as reference for the next iteration.
-            render::render(&mut stdout, &last_frame, &curr_frame, false);
+            let force = theme.is_some() || thread_redraw.swap(false, Ordering::SeqCst);
+            render::render(&mut backend, &last_frame, &curr_frame, force);
             last_frame = curr_frame;
         }
     });
 
     // Populate the game with players, timers, and an army of terminal invaders.
-    let mut overlay = Overlay::new();
-    let mut player = Player::new(&dimensions);
-    let mut invaders = Invaders::new(&dimensions);
+    let mut tick_pacer = Pacer::from_rate(tick_rate);
+    let mut frame_pacer = Pacer::from_rate(frame_rate);
+    let mut last_sent: Option<Frame> = None;
+    let mut capture = false;
+    let mut screenshots = Vec::new();
+    let mut overlay = Overlay::new(tick_pacer.interval());
+    let seed = match challenge {
+        Some(date) => daily::seed(date),
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_nanos() as u64),
+    };
+    let mut game = Game::new(&dimensions, &gameplay, adaptive_mode, enhanced_keys, seed);
+    let mut hud = Hud::new(&dimensions);
+    if let Some(date) = challenge {
+        game.scatter_armies();
+        hud.toast(&if scored {
+            format!("Daily challenge {}", date)
+        } else {
+            format!("Practicing the challenge {}", date)
+        });
+    }
+    let mut help = Help::new(&keys);
+    let mut focus_lost = false;
     let mut instant = Instant::now();
 
     // Creates a game loop that listens for keyboard inputs.
@@ -88,77 +343,553 @@ fn main() -> Result<(), Box<dyn Error>> {
         let delta = instant.elapsed();
         instant = Instant::now();
 
-        // Starts with an empty frame.
-        let mut curr_frame = new_frame(&dimensions);
-
-        // Handles all key-code inputs.
+        // Handles all key-code inputs; the controls of the ship rest while the game is paused.
+        let paused = help.visible || boss.visible || focus_lost;
         while event::poll(Duration::default())? {
-            if let Event::Key(key_event) = event::read()? {
-                match key_event.code {
-                    KeyCode::Left => player.move_left(),
-                    KeyCode::Right => player.move_right(),
-                    KeyCode::Down => player.move_down(),
-                    KeyCode::Up => player.move_up(),
-                    KeyCode::Char(' ') | KeyCode::Enter => player.shoot(),
-                    KeyCode::Char('q') | KeyCode::Esc => break 'gameloop,
+            match event::read()? {
+                Event::Key(key_event) => match (keys.action(&key_event), key_event.kind) {
+
+                    // Stops moving once a held key is released.
+                    (Some(action), KeyEventKind::Release) if action.is_movement() => {
+                        game.input(Input::Release(action))
+                    }
+                    (_, KeyEventKind::Release) => {}
+
+                    // Ignores the terminal's auto-repeat while the ship keeps moving on its own.
+                    (Some(action), KeyEventKind::Repeat)
+                        if action.is_movement() && game.continuous() => {}
+
+                    (Some(action), _) if action.is_movement() && !paused => {
+                        game.input(Input::Press(action))
+                    }
+                    (Some(Action::Shoot), _) if !paused => game.input(Input::Shoot),
+                    (Some(Action::CycleWeapon), KeyEventKind::Press) if !paused => {
+                        game.input(Input::CycleWeapon)
+                    }
+
+                    // Toggles only once per key press.
+                    (Some(Action::Mouse), KeyEventKind::Press) => {
+                        mouse_mode = !mouse_mode;
+                        if mouse_mode {
+                            stdout.execute(EnableMouseCapture)?;
+                        } else {
+                            stdout.execute(DisableMouseCapture)?;
+
+                            // Stops heading for the last column the mouse pointed at.
+                            game.input(Input::ReleaseAll);
+                        }
+                    }
+                    (Some(Action::Mute), KeyEventKind::Press) => audio.toggle_mute(),
+                    (Some(Action::Screenshot), KeyEventKind::Press) => capture = true,
+                    (Some(Action::Help), KeyEventKind::Press) => help.toggle(),
+
+                    // Hides the game behind a build log, or brings it back.
+                    (Some(Action::Boss), KeyEventKind::Press) => {
+                        boss.toggle();
+                        game.input(Input::ReleaseAll);
+                    }
+                    (Some(Action::Suspend), KeyEventKind::Press) => {
+                        game.input(Input::ReleaseAll);
+                        guard.suspend()?;
+                    }
+                    (Some(Action::Quit), _) => break 'gameloop,
+                    _ => {}
+                },
+
+                // Follows the mouse and shoots on left clicks, switching weapons on right ones.
+                Event::Mouse(mouse_event) if mouse_mode && !paused => match mouse_event.kind {
+                    MouseEventKind::Moved | MouseEventKind::Drag(_) => {
+                        game.input(Input::Aim(mouse_event.column))
+                    }
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        game.input(Input::Aim(mouse_event.column));
+                        game.input(Input::Shoot);
+                    }
+                    MouseEventKind::Down(MouseButton::Right) => game.input(Input::CycleWeapon),
                     _ => {}
+                },
+
+                // Pauses while the terminal is in the background, e.g., in another tmux pane.
+                Event::FocusLost => {
+                    focus_lost = true;
+                    game.input(Input::ReleaseAll);
+                }
+                Event::FocusGained => focus_lost = false,
+
+                // Keeps the recording in sync with the terminal size.
+                Event::Resize(cols, rows) => {
+                    if let Some(recorder) = &recorder {
+                        if let Ok(mut recorder) = recorder.lock() {
+                            recorder.resize(cols, rows)?;
+                        }
+                    }
                 }
+                _ => {}
             }
         }
 
-        // Updates player and invader positions.
-        player.update(delta);
-        invaders.update(delta);
-        overlay.update(delta, &dimensions);
+        // Quits gracefully when asked to by a signal.
+        if guard.signal().is_some() {
+            break 'gameloop;
+        }
 
-        // Detects shots hitting invaders.
-        player.detect_hit(&mut invaders);
+        // Suspends when asked to by a signal, and redraws everything once resumed without
+        // letting the time in between count.
+        if guard.suspend_requested() {
+            game.input(Input::ReleaseAll);
+            guard.suspend()?;
+        }
+        if guard.resume()? {
+            if mouse_mode {
+                stdout.execute(EnableMouseCapture)?;
+            }
+            redraw.store(true, Ordering::SeqCst);
+            instant = Instant::now();
+        }
 
-        // Draws player and all remaining invaders.
-        let drawables: Vec<&dyn Drawable> = vec![&player, &invaders];
-        for drawable in drawables {
-            drawable.draw(&mut curr_frame);
+        // Applies changes to the configuration file, or tells why they are invalid.
+        let reloaded = watcher.as_mut().and_then(|watcher| watcher.poll(delta));
+        match reloaded.map(|result| with_keys(result, &args)) {
+            Some(Ok((reloaded, reloaded_keys))) => {
+                game.tune(&reloaded.gameplay(), reloaded.gameplay.adaptive);
+                if let Ok(mut theme) = pending_theme.lock() {
+                    *theme = Some(reloaded.theme.clone());
+                }
+                let visible = help.visible;
+                help = Help::new(&reloaded_keys);
+                help.visible = visible;
+                keys = reloaded_keys;
+                config = reloaded;
+                hud.toast("Reloaded config");
+            }
+            Some(Err(error)) => hud.toast(&error),
+            None => {}
         }
+        hud.update(delta);
 
-        // Draws a debug overlay if mode is enabled.
-        if debug_mode {
-            overlay.draw(&mut curr_frame);
+        // Plays the game on unless it is paused.
+        let paused = help.visible || boss.visible || focus_lost;
+        hud.set_paused(focus_lost);
+        boss.update(delta);
+        if !paused {
+            game.advance(delta);
+        }
+        for sound in game.take_sounds() {
+            audio.play(sound);
         }
+        hud.set_lives(game.lives);
+        hud.set_powers(&game.player.powers.remaining());
+        let arsenal = &game.player.arsenal;
+        hud.set_weapon((arsenal.weapons().len() > 1).then(|| (arsenal.weapon(), arsenal.ammo())));
+        hud.set_score(game.scorecard.score, game.scorecard.wave);
 
-        // Sends the prepared frame to the render channel.
-        let _ = render_tx.send(curr_frame);
+        // Falls back to a visual metronome while the march cannot be heard.
+        hud.set_metronome(if audio.audible() {
+            None
+        } else {
+            Some(game.invaders.beat())
+        });
+        overlay.update(delta, &dimensions, &render_stats);
 
-        // Forces the game loop to slow down to save CPU cycles.
-        thread::sleep(Duration::from_millis(5));
+        // Only draws a new frame if the frame rate allows for it.
+        if frame_pacer.ready() {
 
-        // Winning condition: if invaders are all dead.
-        if invaders.all_killed() {
-            game_won = true;
-            break 'gameloop;
+            // Starts with an empty frame.
+            let mut curr_frame = new_frame(&dimensions);
+
+            // Draws player, all remaining invaders, the heads-up display, and the help screen.
+            let drawables: Vec<&dyn Drawable> = vec![&game, &hud, &help];
+            for drawable in drawables {
+                drawable.draw(&mut curr_frame, &config.theme);
+            }
+
+            // Draws a debug overlay if mode is enabled.
+            if debug_mode {
+                overlay.draw(&mut curr_frame, &config.theme);
+            }
+
+            // Hides everything behind the build log if the boss is around.
+            boss.draw(&mut curr_frame, &config.theme);
+
+            // Saves a screenshot of the frame if one was requested.
+            if capture {
+                capture = false;
+                let stem = PathBuf::from(format!(
+                    "terminal_invaders-{}",
+                    SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map_or(0, |since| since.as_millis())
+                ));
+                screenshots.push(curr_frame.save(&stem, &config.theme));
+            }
+
+            // Skips unchanged frames in low-power mode.
+            if !low_power || last_sent.as_ref() != Some(&curr_frame) {
+                if low_power {
+                    last_sent = Some(curr_frame.clone());
+                }
+
+                // Sends the prepared frame to the renderer, replacing one it did not get to yet.
+                if render::send_frame(&render_tx, curr_frame, &render_stats) == Sent::HungUp {
+                    break 'gameloop;
+                }
+            }
         }
 
-        // Losing condition: if invaders reached bottom.
-        if invaders.reached_bottom() {
+        // Sleeps for the rest of the tick to save CPU cycles.
+        tick_pacer.wait();
+
+        // Ends the game once the last wave is cleared, the invaders reached the bottom, or bombs
+        // took all lives.
+        if game.over() {
             break 'gameloop;
         }
     }
 
-    // Cleans up the threads and terminal once the game ends.
+    // Asks for a name if the score makes it onto the local or team leaderboard of its mode and
+    // difficulty; the screen shows both merged.
+    let mut saved = None;
+    let mut submitted = None;
+    let board = leaderboard::default_path().map(Leaderboard::new);
+    let scorecard = game.scorecard;
+    if let (true, None, Some(board)) = (game.over() && scored, guard.signal(), &board) {
+
+        // Leaves the ranked tables alone once the tuning changed during the game.
+        let ranked = preset && !game.retuned();
+        let entry = Entry {
+            name: String::new(),
+            score: scorecard.score,
+            wave: scorecard.wave,
+            accuracy: scorecard.accuracy(),
+            date: challenge.unwrap_or_else(Date::today).to_string(),
+            mode: challenge.map_or_else(
+                || mode(ranked, adaptive_mode).to_string(),
+                |date| format!("daily {}", date),
+            ),
+            difficulty: difficulty.name().to_string(),
+            replay: Replay::record(&game),
+        };
+        let local = board.load().unwrap_or_default();
+        let shared = team
+            .as_ref()
+            .and_then(|team| team.load().ok())
+            .map_or_else(Vec::new, |(entries, _)| entries);
+        let qualifies = leaderboard::qualifies(&local, &entry)
+            || (team.is_some() && leaderboard::qualifies(&shared, &entry));
+        if scorecard.score > 0 && qualifies {
+            let entries = leaderboard::merge(local, shared.clone());
+            let table = leaderboard::tables(&entries)
+                .remove(&(entry.mode.clone(), entry.difficulty.clone()))
+                .unwrap_or_default();
+            let login = env::var("USER").or_else(|_| env::var("USERNAME"));
+            let mut screen = GameOver::new(scorecard, table, &login.unwrap_or_default());
+            while !screen.done() && guard.signal().is_none() {
+                if event::poll(frame_pacer.interval())? {
+                    if let Event::Key(key_event) = event::read()? {
+                        if key_event.kind != KeyEventKind::Release {
+                            screen.input(&key_event);
+                        }
+                    }
+                }
+                let mut curr_frame = new_frame(&dimensions);
+                let drawables: Vec<&dyn Drawable> = vec![&game, &hud, &screen];
+                for drawable in drawables {
+                    drawable.draw(&mut curr_frame, &config.theme);
+                }
+                if render::send_frame(&render_tx, curr_frame, &render_stats) == Sent::HungUp {
+                    break;
+                }
+            }
+            if let Some(name) = screen.name() {
+                let entry = Entry {
+                    name: name.to_string(),
+                    ..entry
+                };
+                // Ranks the score among the team's records only.
+                if let Some(team) = &team {
+                    let submission = team.submit(entry.clone(), game.seed());
+                    let mut shared = shared;
+                    shared.push(entry.clone());
+                    submitted = Some(submission.map(|_| leaderboard::rank(&shared, &entry)));
+                }
+                saved = Some(board.record(entry));
+            }
+        }
+    }
+
+    // Cleans up the threads and terminal once the game ends; a crashed renderer was reported
+    // by the panic hook already.
     drop(render_tx);
-    render_handle
-        .join()
-        .expect("The render thread should be done by now.");
-    stdout.execute(Show)?;
-    stdout.execute(LeaveAlternateScreen)?;
-    terminal::disable_raw_mode()?;
+    let rendered = render_handle.join();
+    let signal = guard.signal();
+    drop(guard);
+    if rendered.is_err() {
+        return Err("The render thread crashed.".into());
+    }
+
+    // Quits with the conventional exit code when interrupted.
+    if let Some(signal) = signal {
+        process::exit(128 + signal);
+    }
+
+    // Lists the screenshots taken during the game.
+    for screenshot in screenshots {
+        match screenshot {
+            Ok(paths) => {
+                for path in paths {
+                    println!("Saved screenshot {}", path.display());
+                }
+            }
+            Err(error) => println!("Failed to save screenshot: {}", error),
+        }
+    }
 
     // Displays results.
-    if game_won {
-        println!("You won!");
-    } else {
-        println!("Game over!")
+    println!(
+        "{} You scored {} in wave {} with an accuracy of {}%.",
+        if game.won() { "You won!" } else { "Game over!" },
+        scorecard.score,
+        scorecard.wave,
+        scorecard.accuracy()
+    );
+    if !scored {
+        println!("That was practice; today's scored attempt at the daily challenge is used up.");
+    }
+    match (saved, &board) {
+        (Some(Ok(Some(rank))), Some(board)) => println!(
+            "You are number {} on the leaderboard {}; see it with `terminal_invaders scores`.",
+            rank,
+            board.path().display()
+        ),
+        (Some(Err(error)), Some(board)) => println!(
+            "Failed to save the score to {}: {}",
+            board.path().display(),
+            error
+        ),
+        _ => {}
+    }
+    match (submitted, &team) {
+        (Some(Ok(Some(rank))), Some(team)) => println!(
+            "You are number {} on the team leaderboard {}.",
+            rank,
+            team.dir().display()
+        ),
+        (Some(Ok(None)), Some(team)) => {
+            println!(
+                "Your score went to the team leaderboard {}.",
+                team.dir().display()
+            )
+        }
+        (Some(Err(error)), Some(team)) => println!(
+            "Failed to submit the score to {}: {}",
+            team.dir().display(),
+            error
+        ),
+        _ => {}
     }
+    audio.play(Sound::GameOver);
+
+    // Lets the last sound effects play out.
+    audio.wait();
 
     // Shuts down normally.
     Ok(())
 }
+
+// Parses a positive rate per second from the command line.
+fn parse_rate(value: &str) -> Result<u32, String> {
+    match value.parse::<u32>() {
+        Ok(rate) if rate > 0 => Ok(rate),
+        _ => Err(format!("expected a positive number, got '{}'", value)),
+    }
+}
+
+// Applies the choices made on the command line over the configuration file.
+fn override_config(config: &mut Config, args: &ArgMatches) {
+    if let Some(preset) = args.value_of("preset") {
+        config.controls.preset = Some(preset.to_string());
+    }
+    if let Some(difficulty) = args.value_of("difficulty") {
+        config.gameplay.difficulty = difficulty.parse().expect("Clap should only allow presets.");
+    }
+    if args.is_present("adaptive") {
+        config.gameplay.adaptive = true;
+    }
+
+    // Plays the daily challenge by the book, leaving out all tuning.
+    if args.is_present("daily") {
+        config.gameplay = GameplayConfig {
+            difficulty: config.gameplay.difficulty,
+            ..GameplayConfig::default()
+        };
+    }
+}
+
+// Returns the leaderboard mode of the game: games with a custom tuning are ranked apart.
+fn mode(ranked: bool, adaptive: bool) -> &'static str {
+    match (ranked, adaptive) {
+        (false, _) => "custom",
+        (true, true) => "adaptive",
+        (true, false) => "classic",
+    }
+}
+
+// Plays the runs in the given files again, or on the local and team leaderboards, and reports
+// which ones hold up; fails if any does not.
+fn verify_scores(
+    files: Option<Vec<PathBuf>>,
+    team: Option<TeamLeaderboard>,
+) -> Result<(), Box<dyn Error>> {
+    let files = files.unwrap_or_else(|| {
+        let local = leaderboard::default_path().filter(|path| path.exists());
+        local
+            .into_iter()
+            .chain(team.map(|team| team.dir().to_path_buf()))
+            .collect()
+    });
+    let mut checked = 0;
+    let mut unverifiable = 0;
+    let mut failed = 0;
+    for file in files {
+        for (path, entry, verdict) in runs(&file)? {
+            checked += 1;
+            let time = match verdict {
+                Ok(time) => format!("{}:{:02}", time.as_secs() / 60, time.as_secs() % 60),
+                Err(rejection @ Rejection::Unverifiable(_)) => {
+                    unverifiable += 1;
+                    rejection.to_string()
+                }
+                Err(rejection) => {
+                    failed += 1;
+                    format!("FAILED: {}", rejection)
+                }
+            };
+            let run = entry.map_or_else(
+                || format!("{:<8} {:>7}  {:>7}", "?", "", ""),
+                |entry| {
+                    format!(
+                        "{:<8} {:>7}  wave {:>2}",
+                        entry.name, entry.score, entry.wave
+                    )
+                },
+            );
+            println!("{}  {}  {}", run, path.display(), time);
+        }
+    }
+
+    // Fails only for runs that do not hold up; those played under other rules are no forgery.
+    match (checked, failed, unverifiable) {
+        (0, _, _) => println!("No runs to check."),
+        (_, 0, 0) => println!("Every run holds up, {} checked.", checked),
+        (_, 0, _) => println!(
+            "Every run that could be played again holds up, {} checked, {} unverifiable.",
+            checked, unverifiable
+        ),
+        _ => {
+            eprintln!("{} of {} runs do not hold up.", failed, checked);
+            process::exit(1);
+        }
+    }
+    Ok(())
+}
+
+// Provides a run found in a file with the verdict of playing it again; files that cannot be
+// read count as a run without an entry.
+type Run = (PathBuf, Option<Entry>, Result<Duration, Rejection>);
+
+// Lists the runs of a team record, a leaderboard file, or a directory of team records.
+fn runs(path: &Path) -> io::Result<Vec<Run>> {
+    if path.is_dir() {
+        let mut files: Vec<PathBuf> = fs::read_dir(path)?
+            .map(|file| file.map(|file| file.path()))
+            .collect::<io::Result<_>>()?;
+        files.retain(|file| {
+            file.extension()
+                .is_some_and(|extension| extension == "toml")
+        });
+        files.sort();
+        return Ok(files.iter().flat_map(|file| runs_in_file(file)).collect());
+    }
+    Ok(runs_in_file(path))
+}
+
+// Lists the runs of a team record or a leaderboard file.
+fn runs_in_file(path: &Path) -> Vec<Run> {
+    if let Some(record) = team::read(path) {
+        let verdict = record.verify();
+        return vec![(path.to_path_buf(), Some(record.entry), verdict)];
+    }
+    match Leaderboard::new(path.to_path_buf()).load() {
+        Ok(entries) => entries
+            .into_iter()
+            .map(|entry| {
+                let verdict = replay::verify(&entry);
+                (path.to_path_buf(), Some(entry), verdict)
+            })
+            .collect(),
+        Err(error) => vec![(
+            path.to_path_buf(),
+            None,
+            Err(Rejection::Invalid(format!("unreadable: {}", error))),
+        )],
+    }
+}
+
+// Returns the team leaderboard if a directory was given.
+fn team_leaderboard(args: &ArgMatches) -> Option<TeamLeaderboard> {
+    args.value_of("leaderboard-dir").map(|dir| {
+        let team = TeamLeaderboard::new(PathBuf::from(dir));
+        match team::default_cache_path() {
+            Some(cache) => team.with_cache(cache),
+            None => team,
+        }
+    })
+}
+
+// Prints all tables of the leaderboard, merged with the team's if given; warns about team
+// records whose runs do not hold up.
+fn print_scores(team: Option<&TeamLeaderboard>) -> Result<(), Box<dyn Error>> {
+    let path = leaderboard::default_path().ok_or("There is no directory for the leaderboard.")?;
+    let board = Leaderboard::new(path.clone());
+    let (mut entries, corrupt) = board.load_or_set_aside().map_err(|error| {
+        format!(
+            "Failed to read the leaderboard {}: {}",
+            path.display(),
+            error
+        )
+    })?;
+    if let Some(corrupt) = corrupt {
+        eprintln!(
+            "The leaderboard {} was corrupted and was moved to {}; starting a new one.",
+            path.display(),
+            corrupt.display()
+        );
+    }
+    if let Some(team) = team {
+        let (shared, rejected) = team.load().map_err(|error| {
+            format!(
+                "Failed to read the team leaderboard {}: {}",
+                team.dir().display(),
+                error
+            )
+        })?;
+        for (path, rejection) in rejected {
+            eprintln!("Skipped {}: {}.", path.display(), rejection);
+        }
+        entries = leaderboard::merge(entries, shared);
+    }
+    print!("{}", leaderboard::format_tables(&entries));
+    Ok(())
+}
+
+// Resolves the key bindings of a reloaded configuration, keeping the choices made on the
+// command line.
+fn with_keys(
+    reloaded: Result<Config, String>,
+    args: &ArgMatches,
+) -> Result<(Config, KeyMap), String> {
+    let mut reloaded = reloaded?;
+    override_config(&mut reloaded, args);
+    let keys = reloaded.key_map().map_err(|error| error.to_string())?;
+    Ok((reloaded, keys))
+}
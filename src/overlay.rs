@@ -16,45 +16,52 @@ use std::time::Duration;
 
 use rusty_time::prelude::Timer;
 
+use crate::config::Theme;
 use crate::frame::{Drawable, Frame};
+use crate::render::RenderStats;
 
 // Provides an overlay with customizable strings.
 pub struct Overlay {
     timer: Timer,
+    interval: Duration,
     lines: Vec<String>,
 }
 
 // Implements the overlay.
 impl Overlay {
 
-    // Creates a new overlay.
-    pub fn new() -> Self {
+    // Creates a new overlay for a game loop targeting the given update interval.
+    pub fn new(interval: Duration) -> Self {
         Self {
 
             // Provides a timer updating the overlay every 100ms.
             timer: Timer::from_millis(100),
 
+            // Keeps the targeted interval to compute the lag.
+            interval,
+
             // Creates an empty string collection.
-            lines: vec![" ".to_string(); 2],
+            lines: vec![" ".to_string(); 3],
         }
     }
 
     // Updates the overlay with debugging information.
-    pub fn update(&mut self, delta: Duration, dimensions: &Vec<u16>) {
+    pub fn update(&mut self, delta: Duration, dimensions: &[u16], stats: &RenderStats) {
         self.timer.update(delta);
         if self.timer.ready {
             let fps = 1_f64 / delta.as_secs_f64();
-            let lag = (delta.as_nanos() as f64 - 5_000_000_f64) / 1_000_000_f64;
+            let lag = (delta.as_secs_f64() - self.interval.as_secs_f64()) * 1_000_f64;
             self.lines[0] = format!(
                 "dimension: {:?} cols x {:?} rows",
                 dimensions[0], dimensions[1]
             );
             self.lines[1] = format!(
-                "delta: {:.5}s; render(): {:.2}/s; duration: {:5}ms",
+                "delta: {:.5}s; update(): {:.2}/s; lag: {:.2}ms",
                 delta.as_secs_f64(),
                 fps,
                 lag
             );
+            self.lines[2] = format!("dropped frames: {}", stats.dropped());
             self.timer.reset();
         }
     }
@@ -64,8 +71,9 @@ impl Overlay {
 impl Drawable for Overlay {
 
     // Draws the overlay on a given frame.
-    fn draw(&self, frame: &mut Frame) {
+    fn draw(&self, frame: &mut Frame, _theme: &Theme) {
         frame[0][0] = self.lines[0].clone();
         frame[0][1] = self.lines[1].clone();
+        frame[0][2] = self.lines[2].clone();
     }
 }
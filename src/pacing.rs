@@ -0,0 +1,158 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{FRAME_RATE, LOW_POWER_FRAME_RATE, LOW_POWER_TICK_RATE, TICK_RATE};
+
+// Returns the default rates of simulation updates and rendered frames per second; low-power
+// mode lowers both.
+pub fn rates(low_power: bool) -> (u32, u32) {
+    if low_power {
+        (LOW_POWER_TICK_RATE, LOW_POWER_FRAME_RATE)
+    } else {
+        (TICK_RATE, FRAME_RATE)
+    }
+}
+
+// Provides the time a pacer goes by, and a way to wait for it to pass.
+pub trait Clock {
+
+    // Returns the current time.
+    fn now(&self) -> Instant;
+
+    // Waits for the given time to pass.
+    fn sleep(&self, duration: Duration);
+}
+
+// Provides the system's clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+// Implements the clock on top of the system's clock.
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+// Provides a clock that only moves when told to or slept on, e.g., for tests; clones share the
+// same time.
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    now: Rc<Cell<Instant>>,
+}
+
+// Implements the manual clock.
+impl ManualClock {
+
+    // Creates a new manual clock standing at the current time.
+    pub fn new() -> Self {
+        Self {
+            now: Rc::new(Cell::new(Instant::now())),
+        }
+    }
+
+    // Moves the clock forward by the given time.
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+}
+
+// Implements a manual clock standing at the current time.
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Implements the clock by moving the manual clock, without waiting.
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
+
+// Provides a pacer that schedules work at a fixed rate per second.
+pub struct Pacer<C: Clock = SystemClock> {
+    clock: C,
+    interval: Duration,
+    deadline: Instant,
+}
+
+// Implements the pacer on the system's clock.
+impl Pacer {
+
+    // Creates a new pacer running the given number of times per second.
+    pub fn from_rate(rate: u32) -> Self {
+        Self::with_clock(rate, SystemClock)
+    }
+}
+
+// Implements the pacer.
+impl<C: Clock> Pacer<C> {
+
+    // Creates a new pacer running the given number of times per second on the given clock.
+    pub fn with_clock(rate: u32, clock: C) -> Self {
+        let interval = Duration::from_secs(1) / rate.max(1);
+        Self {
+            deadline: clock.now() + interval,
+            clock,
+            interval,
+        }
+    }
+
+    // Returns the time between two scheduled runs.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    // Determines whether the deadline passed and schedules the next one if so.
+    pub fn ready(&mut self) -> bool {
+        let now = self.clock.now();
+        if now < self.deadline {
+            return false;
+        }
+        self.advance(now);
+        true
+    }
+
+    // Sleeps for whatever is left of the interval after the work done so far.
+    pub fn wait(&mut self) {
+        let now = self.clock.now();
+        if now < self.deadline {
+            self.clock.sleep(self.deadline - now);
+        }
+        self.advance(self.clock.now());
+    }
+
+    // Moves the deadline forward by one interval without trying to catch up on missed ones.
+    fn advance(&mut self, now: Instant) {
+        self.deadline += self.interval;
+        if self.deadline < now {
+            self.deadline = now + self.interval;
+        }
+    }
+}
@@ -15,37 +15,67 @@
 use std::time::Duration;
 
 use crate::{
+    config::{Gameplay, Theme},
     frame::{Drawable, Frame},
     invaders::Invaders,
+    powerup::{Power, PowerUps},
     shot::Shot,
-    NUM_SHOTS,
+    weapon::{Arsenal, Weapon},
 };
 
+// Provides what the shots hit since the last update: the shots that damaged an invader, and
+// where invaders were killed.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Hits {
+    pub shots: u32,
+    pub kills: Vec<(u16, u16)>,
+}
+
 // Provides a player struct with coordinates, bounds, and shots.
 pub struct Player {
     x: u16,
     y: u16,
     bounds: Vec<u16>,
     shots: Vec<Shot>,
+    gameplay: Gameplay,
+    pub powers: PowerUps,
+    pub arsenal: Arsenal,
 }
 
 // Implements the player struct.
 impl Player {
 
     // Creates a new player within the given boundaries.
-    pub fn new(dimensions: &Vec<u16>) -> Self {
+    pub fn new(dimensions: &[u16]) -> Self {
         Self {
 
             // Centers the player on the bottom of the terminal.
             x: dimensions[0] / 2,
-            y: dimensions[1] - 1,
-            bounds: dimensions.clone(),
+            y: dimensions[1].saturating_sub(1),
+            bounds: dimensions.to_vec(),
 
             // Prepares the shots for the player.
             shots: Vec::new(),
+
+            // Uses the default tuning until told otherwise.
+            gameplay: Gameplay::default(),
+
+            // Starts without power-ups, and with nothing but the blaster.
+            powers: PowerUps::new(),
+            arsenal: Arsenal::default(),
         }
     }
 
+    // Applies the tuning to the player and all future shots.
+    pub fn tune(&mut self, gameplay: &Gameplay) {
+        self.gameplay = *gameplay;
+    }
+
+    // Returns the player position as x, y.
+    pub fn position(&self) -> (u16, u16) {
+        (self.x, self.y)
+    }
+
     // Moves the player position left.
     pub fn move_left(&mut self) {
         if self.x > 0 {
@@ -55,7 +85,7 @@ impl Player {
 
     // Moves the player position right.
     pub fn move_right(&mut self) {
-        if self.x < self.bounds[0] - 1 {
+        if self.x < self.bounds[0].saturating_sub(1) {
             self.x += 1;
         }
     }
@@ -69,47 +99,119 @@ impl Player {
 
     // Moves the player position down.
     pub fn move_down(&mut self) {
-        if self.y < self.bounds[1] - 1 {
+        if self.y < self.bounds[1].saturating_sub(1) {
             self.y += 1;
         }
     }
 
-    // Shoots at the invaders.
-    pub fn shoot(&mut self) {
+    // Adds the weapon to the arsenal with its full ammo, and selects it.
+    pub fn arm(&mut self, weapon: Weapon) {
+        let spec = weapon.spec(&self.gameplay);
+        self.arsenal.arm(weapon, spec.ammo);
+    }
 
-        // We only have a limit of `NUM_SHOTS`.
-        if self.shots.len() <= NUM_SHOTS {
-            self.shots.push(Shot::new(self.x, self.y - 1));
+    // Grants the power of a caught capsule; weapon powers switch to their weapon, without a
+    // limit of ammo, for as long as they last.
+    pub fn power_up(&mut self, power: Power) {
+        self.powers.activate(power);
+        if let Some(weapon) = power.weapon() {
+            self.arsenal.arm(weapon, None);
         }
     }
 
+    // Shoots at the invaders with the selected weapon; returns the number of shots fired.
+    pub fn shoot(&mut self) -> usize {
+
+        // Every weapon has a limit of shots in the air, and some take a while to cool down; a
+        // volley aims at the columns of the weapon's spread that are within bounds, and is only
+        // fired if all of its shots fit under the limit.
+        let weapon = self.arsenal.weapon();
+        let spec = weapon.spec(&self.gameplay);
+        let columns: Vec<u16> = spec
+            .spread
+            .iter()
+            .filter_map(|offset| self.x.checked_add_signed(*offset))
+            .filter(|x| *x < self.bounds[0])
+            .collect();
+        let flying = self
+            .shots
+            .iter()
+            .filter(|shot| shot.weapon == weapon)
+            .count();
+        if flying + columns.len() > spec.cap || !self.arsenal.ready() {
+            return 0;
+        }
+
+        // Fires the volley.
+        let mut gameplay = self.gameplay;
+        gameplay.shot_millis = spec.shot_millis;
+        for x in columns.iter() {
+            let mut shot = Shot::new(*x, self.y.saturating_sub(1), &gameplay);
+            shot.piercing = spec.piercing;
+            shot.damage = spec.damage;
+            shot.weapon = weapon;
+            self.shots.push(shot);
+        }
+        self.arsenal.fire(&spec);
+        columns.len()
+    }
+
     // Updates the player position.
     pub fn update(&mut self, delta: Duration) {
 
-        // Updates the shot positions.
+        // Only retain the shots that are still alive, then updates their positions.
+        self.shots.retain(|shot| !shot.died());
         for shot in self.shots.iter_mut() {
             shot.update(delta);
         }
 
-        // Only retain the shots that are still alive.
-        self.shots.retain(|shot| !shot.died());
+        // Counts down the power-ups, puts the weapons of those that ran out away, and cools down
+        // the weapons.
+        for power in self.powers.update(delta) {
+            if let Some(weapon) = power.weapon() {
+                self.arsenal.disarm(weapon);
+            }
+        }
+        self.arsenal.update(delta);
     }
 
-    // Detects a hit of a shot and an invader.
-    pub fn detect_hit(&mut self, invaders: &mut Invaders) {
+    // Detects hits of shots and invaders along the way the shots flew since the last update.
+    pub fn detect_hit(&mut self, invaders: &mut Invaders) -> Hits {
+        let mut hits = Hits::default();
         for shot in self.shots.iter_mut() {
 
             // Only non-exploding shots can hit invaders.
-            if !shot.exploding {
+            if shot.exploding {
+                continue;
+            }
+            for (x, y) in invaders.in_path(shot.x, shot.y, shot.from_y) {
+
+                // Tries to hit the invaders in the order the shot reached them.
+                let Some(killed) = invaders.hit_invader_at(x, y, shot.damage) else {
+                    continue;
+                };
+                if killed {
+                    hits.kills.push((x, y));
+                }
 
-                // Tries to kill an invader at the current position.
-                if invaders.kill_invader_at(shot.x, shot.y) {
+                // Counts every shot once, however many invaders it pierces through.
+                if !shot.hit {
+                    shot.hit = true;
+                    hits.shots += 1;
+                }
 
-                    // Explodes if invader is killed.
+                // Explodes on the invader, unless it pierces through one it killed.
+                if !(shot.piercing && killed) {
+                    shot.y = y;
                     shot.explode();
+                    break;
                 }
             }
         }
+
+        // Spends the shots that reached the top.
+        self.shots.retain(|shot| !shot.died());
+        hits
     }
 }
 
@@ -117,10 +219,10 @@ impl Player {
 impl Drawable for Player {
 
     // Draws the player and shots on the given frame.
-    fn draw(&self, frame: &mut Frame) {
-        frame[self.x as usize][self.y as usize] = "△".to_string();
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+        frame[self.x as usize][self.y as usize] = theme.player.clone();
         for shot in self.shots.iter() {
-            shot.draw(frame);
+            shot.draw(frame, theme);
         }
     }
 }
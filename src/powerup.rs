@@ -0,0 +1,194 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use rusty_time::prelude::Timer;
+
+use crate::{
+    config::Theme,
+    frame::{Drawable, Frame},
+    weapon::Weapon,
+    CAPSULE_FALL_MILLIS, POWER_UP_MILLIS,
+};
+
+// Provides the powers that capsules grant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Power {
+    RapidFire,
+    Spread,
+    Piercing,
+    Shield,
+    ExtraLife,
+    SlowTime,
+}
+
+// Implements the powers.
+impl Power {
+
+    // Lists all powers.
+    pub const ALL: [Power; 6] = [
+        Power::RapidFire,
+        Power::Spread,
+        Power::Piercing,
+        Power::Shield,
+        Power::ExtraLife,
+        Power::SlowTime,
+    ];
+
+    // Returns the name of the power as shown on the heads-up display.
+    pub fn name(self) -> &'static str {
+        match self {
+            Power::RapidFire => "rapid",
+            Power::Spread => "spread",
+            Power::Piercing => "piercing",
+            Power::Shield => "shield",
+            Power::ExtraLife => "life",
+            Power::SlowTime => "slow",
+        }
+    }
+
+    // Returns the letter the capsule of the power is marked with.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Power::RapidFire => "R",
+            Power::Spread => "W",
+            Power::Piercing => "P",
+            Power::Shield => "S",
+            Power::ExtraLife => "+",
+            Power::SlowTime => "T",
+        }
+    }
+
+    // Returns the weapon the power switches to while it lasts.
+    pub fn weapon(self) -> Option<Weapon> {
+        match self {
+            Power::RapidFire => Some(Weapon::Rapid),
+            Power::Spread => Some(Weapon::Spread),
+            Power::Piercing => Some(Weapon::Laser),
+            _ => None,
+        }
+    }
+
+    // Returns how long the power lasts; extra lives are granted right away and last forever.
+    pub fn duration(self) -> Option<Duration> {
+        match self {
+            Power::ExtraLife => None,
+            _ => Some(Duration::from_millis(POWER_UP_MILLIS)),
+        }
+    }
+}
+
+// Provides a capsule that a killed invader dropped, falling down for the ship to catch.
+pub struct Capsule {
+    pub x: u16,
+    pub y: u16,
+    pub power: Power,
+    timer: Timer,
+}
+
+// Implements the capsule.
+impl Capsule {
+
+    // Creates a new capsule of the power at the given position.
+    pub fn new(x: u16, y: u16, power: Power) -> Self {
+        Self {
+            x,
+            y,
+            power,
+
+            // It falls slower than bombs, so that there is time to catch it.
+            timer: Timer::from_millis(CAPSULE_FALL_MILLIS),
+        }
+    }
+
+    // Updates the capsule's position; returns whether it is still within the given height.
+    pub fn update(&mut self, delta: Duration, height: u16) -> bool {
+        self.timer.update(delta);
+        if self.timer.ready {
+            self.timer.reset();
+            self.y += 1;
+        }
+        self.y < height
+    }
+}
+
+// Implements the drawable trait for capsules.
+impl Drawable for Capsule {
+
+    // Draws a capsule as the letter of its power.
+    fn draw(&self, frame: &mut Frame, _theme: &Theme) {
+        frame[self.x as usize][self.y as usize] = self.power.symbol().to_string();
+    }
+}
+
+// Provides the powers that are currently active, each with its own timer.
+#[derive(Default)]
+pub struct PowerUps {
+    active: Vec<(Power, Timer)>,
+}
+
+// Implements the active powers.
+impl PowerUps {
+
+    // Creates a new set without active powers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Activates the power, or starts its timer over if it is active already.
+    pub fn activate(&mut self, power: Power) {
+        let Some(duration) = power.duration() else {
+            return;
+        };
+        self.active.retain(|(active, _)| *active != power);
+        let millis = duration.as_millis() as u64;
+        self.active.push((power, Timer::from_millis(millis)));
+    }
+
+    // Determines whether the power is active.
+    pub fn active(&self, power: Power) -> bool {
+        self.active.iter().any(|(active, _)| *active == power)
+    }
+
+    // Uses the power up, e.g., a shield that took a hit; returns whether it was active.
+    pub fn consume(&mut self, power: Power) -> bool {
+        let before = self.active.len();
+        self.active.retain(|(active, _)| *active != power);
+        self.active.len() != before
+    }
+
+    // Counts down the active powers and ends those that ran out; returns the ended powers.
+    pub fn update(&mut self, delta: Duration) -> Vec<Power> {
+        for (_, timer) in self.active.iter_mut() {
+            timer.update(delta);
+        }
+        let ended = self
+            .active
+            .iter()
+            .filter(|(_, timer)| timer.ready)
+            .map(|(power, _)| *power)
+            .collect();
+        self.active.retain(|(_, timer)| !timer.ready);
+        ended
+    }
+
+    // Lists the active powers with the time they have left, in the order they were activated.
+    pub fn remaining(&self) -> Vec<(Power, Duration)> {
+        self.active
+            .iter()
+            .map(|(power, timer)| (*power, timer.time_left))
+            .collect()
+    }
+}
@@ -12,46 +12,166 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::backend::Backend;
 use crate::frame::Frame;
 
-use crossterm::{
-    cursor::MoveTo,
-    style::{Color, SetBackgroundColor},
-    terminal::{Clear, ClearType},
-    QueueableCommand,
-};
-use std::io::Stdout;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+// Provides render statistics shared between the game and the render thread.
+#[derive(Default)]
+pub struct RenderStats {
+    dropped: AtomicUsize,
+}
+
+// Implements the render statistics.
+impl RenderStats {
+
+    // Creates new render statistics without any dropped frames.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Counts the given number of frames as dropped.
+    pub fn drop_frames(&self, count: usize) {
+        self.dropped.fetch_add(count, Ordering::Relaxed);
+    }
+
+    // Returns the number of frames dropped so far.
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+// Provides the frame waiting for the render thread, and whether either side hung up.
+#[derive(Default)]
+struct Slot {
+    frame: Option<Frame>,
+    hung_up: bool,
+}
+
+// Provides a mailbox holding a single frame, shared between the game and the render thread.
+#[derive(Default)]
+struct Mailbox {
+    slot: Mutex<Slot>,
+    delivered: Condvar,
+}
+
+// Implements the mailbox.
+impl Mailbox {
+
+    // Locks the slot; a side that panicked while holding it left a frame or nothing behind, both
+    // of which are fine to go on with.
+    fn lock(&self) -> MutexGuard<'_, Slot> {
+        self.slot
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // Hangs up, and wakes the render thread to notice.
+    fn hang_up(&self) {
+        self.lock().hung_up = true;
+        self.delivered.notify_all();
+    }
+}
+
+// Provides the game's end of the mailbox; hangs up when dropped.
+pub struct FrameSender {
+    mailbox: Arc<Mailbox>,
+}
+
+// Implements hanging up the game's end.
+impl Drop for FrameSender {
+    fn drop(&mut self) {
+        self.mailbox.hang_up();
+    }
+}
+
+// Provides the render thread's end of the mailbox; hangs up when dropped.
+pub struct FrameReceiver {
+    mailbox: Arc<Mailbox>,
+}
+
+// Implements hanging up the render thread's end.
+impl Drop for FrameReceiver {
+    fn drop(&mut self) {
+        self.mailbox.hang_up();
+    }
+}
+
+// Creates a mailbox between the game and the render thread that holds a single frame: a newer
+// frame replaces the one still waiting, so that the render thread always draws the latest.
+pub fn mailbox() -> (FrameSender, FrameReceiver) {
+    let mailbox = Arc::new(Mailbox::default());
+    (
+        FrameSender {
+            mailbox: Arc::clone(&mailbox),
+        },
+        FrameReceiver { mailbox },
+    )
+}
+
+// Provides what became of a frame sent to the render thread.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sent {
+    Queued,
+    HungUp,
+}
+
+// Sends a frame to the render thread without blocking; drops the stale frame still waiting, if
+// any, in its favor.
+pub fn send_frame(render_tx: &FrameSender, frame: Frame, stats: &RenderStats) -> Sent {
+    let mut slot = render_tx.mailbox.lock();
+    if slot.hung_up {
+        return Sent::HungUp;
+    }
+    if slot.frame.replace(frame).is_some() {
+        stats.drop_frames(1);
+    }
+    drop(slot);
+    render_tx.mailbox.delivered.notify_one();
+    Sent::Queued
+}
+
+// Waits for the latest frame. Returns none once the game thread hung up and its last frame was
+// taken.
+pub fn recv_latest(render_rx: &FrameReceiver) -> Option<Frame> {
+    let mailbox = &render_rx.mailbox;
+    let mut slot = mailbox.lock();
+    loop {
+        if let Some(frame) = slot.frame.take() {
+            return Some(frame);
+        }
+        if slot.hung_up {
+            return None;
+        }
+        slot = mailbox
+            .delivered
+            .wait(slot)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+    }
+}
 
 // Renders the current frame over the last frame.
-pub fn render(stdout: &mut Stdout, last_frame: &Frame, curr_frame: &Frame, force: bool) {
+pub fn render<B: Backend>(backend: &mut B, last_frame: &Frame, curr_frame: &Frame, force: bool) {
     // Forces the entire frame canvas to reset.
     if force {
-        stdout
-            .queue(SetBackgroundColor(Color::Grey))
-            .expect("Background color should be set to grey!");
-        stdout
-            .queue(Clear(ClearType::All))
-            .expect("It should clear the entire STDOUT.");
-        stdout
-            .queue(SetBackgroundColor(Color::Black))
-            .expect("Background color should be set to black!");
+        backend.clear().expect("It should clear the entire canvas.");
     }
 
     // Iterates all colums over x coordinates.
     for (x, col) in curr_frame.iter().enumerate() {
         // Iterates all characters of y coordinates.
         for (y, s) in col.iter().enumerate() {
-            // Moves the queue to the requested position of x, y.
-            stdout.queue(MoveTo(x as u16, y as u16)).expect(&format!(
-                "It shoud move to the position {}, {}.",
-                x.to_string(),
-                y.to_string()
-            ));
-
-            // Updates the frame if the character changed.
+            // Updates the frame at the position x, y if the character changed.
             if *s != last_frame[x][y] || force {
-                print!("{}", s);
+                backend
+                    .draw(x as u16, y as u16, s)
+                    .unwrap_or_else(|_| panic!("It shoud draw at the position {}, {}.", x, y));
             }
         }
     }
+
+    // Presents the frame.
+    backend.flush().expect("It should present the frame.");
 }
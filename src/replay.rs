@@ -0,0 +1,258 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Gameplay,
+    daily,
+    date::Date,
+    difficulty::Difficulty,
+    game::{Game, Input},
+    leaderboard::Entry,
+    DAILY_HEIGHT, DAILY_WIDTH, STEP_MILLIS,
+};
+
+// Provides the version of the rules runs are played under; bumped whenever a change to the game
+// makes the same inputs play out differently, since older runs can no longer be played again.
+pub const RULES_VERSION: u32 = 3;
+
+// Provides the largest playfield that is played again, which keeps forged replays from
+// exhausting memory.
+const MAX_SIDE: u16 = 1000;
+
+// Provides the longest game that is played again, two hours, and the longest timings of its
+// tuning, which keep forged replays from playing on for good.
+const MAX_TICKS: u64 = 2 * 60 * 60 * 1000 / STEP_MILLIS;
+const MAX_TIMING_MILLIS: u64 = 30_000;
+
+// Provides everything needed to play a run again: its seed, playfield, tuning, and inputs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Replay {
+
+    // Keeps the version of the rules the run was played under; replays recorded before the
+    // rules were versioned lack it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rules: Option<u32>,
+
+    // Keeps the seed as hex, since TOML integers stop at i64.
+    pub seed: String,
+    pub width: u16,
+    pub height: u16,
+    pub continuous: bool,
+    pub adaptive: bool,
+    #[serde(default)]
+    pub scattered: bool,
+    pub ticks: u64,
+
+    // Logs the inputs as `<step>:<input>`, separated by spaces, e.g., `12:+left 20:shoot`.
+    pub inputs: String,
+    pub gameplay: Gameplay,
+}
+
+// Implements the replay.
+impl Replay {
+
+    // Records the replay of a finished game; returns none if the tuning changed during the
+    // game, which cannot be replayed.
+    pub fn record(game: &Game) -> Option<Self> {
+        if game.retuned() {
+            return None;
+        }
+        let inputs: Vec<String> = game
+            .inputs()
+            .iter()
+            .map(|(tick, input)| format!("{}:{}", tick, input))
+            .collect();
+        Some(Self {
+            rules: Some(RULES_VERSION),
+            seed: format!("{:016x}", game.seed()),
+            width: game.dimensions()[0],
+            height: game.dimensions()[1],
+            continuous: game.continuous(),
+            adaptive: game.adaptive(),
+            scattered: game.scattered(),
+            ticks: game.ticks(),
+            inputs: inputs.join(" "),
+            gameplay: game.gameplay(),
+        })
+    }
+
+    // Plays the run again under the current rules without a terminal and returns the finished
+    // game.
+    pub fn play(&self) -> Result<Game, String> {
+        let seed = u64::from_str_radix(&self.seed, 16)
+            .map_err(|_| format!("invalid seed '{}'", self.seed))?;
+        if !(1..=MAX_SIDE).contains(&self.width) || !(1..=MAX_SIDE).contains(&self.height) {
+            return Err(format!("invalid playfield {}x{}", self.width, self.height));
+        }
+        if self.ticks > MAX_TICKS {
+            return Err(format!(
+                "the game lasts longer than {}h",
+                MAX_TICKS * STEP_MILLIS / 3_600_000
+            ));
+        }
+        self.gameplay.validate()?;
+        for (name, value) in [
+            ("march_millis", self.gameplay.march_millis),
+            ("shot_millis", self.gameplay.shot_millis),
+            ("explosion_millis", self.gameplay.explosion_millis),
+            ("move_millis", self.gameplay.move_millis),
+            ("bomb_millis", self.gameplay.bomb_millis),
+        ] {
+            if value > MAX_TIMING_MILLIS {
+                return Err(format!(
+                    "gameplay.{} ({}) exceeds {}",
+                    name, value, MAX_TIMING_MILLIS
+                ));
+            }
+        }
+        let mut inputs = Vec::new();
+        for logged in self.inputs.split_whitespace() {
+            let (tick, input) = logged
+                .split_once(':')
+                .ok_or_else(|| format!("invalid input '{}'", logged))?;
+            let tick: u64 = tick
+                .parse()
+                .map_err(|_| format!("invalid step in '{}'", logged))?;
+            inputs.push((tick, input.parse::<Input>()?));
+        }
+        if inputs.windows(2).any(|pair| pair[0].0 > pair[1].0) {
+            return Err("inputs out of order".to_string());
+        }
+
+        // Feeds every input right before the step it was made at, just like the game loop.
+        let mut game = Game::new(
+            &[self.width, self.height],
+            &self.gameplay,
+            self.adaptive,
+            self.continuous,
+            seed,
+        );
+        if self.scattered {
+            game.scatter_armies();
+        }
+        let mut inputs = inputs.into_iter().peekable();
+        while game.ticks() < self.ticks && !game.over() {
+            while let Some((_, input)) = inputs.next_if(|(tick, _)| *tick <= game.ticks()) {
+                game.input(input);
+            }
+            game.step();
+        }
+        if inputs.next().is_some() {
+            return Err("inputs after the end of the game".to_string());
+        }
+        Ok(game)
+    }
+}
+
+// Provides why a run is not confirmed: it cannot be played again, e.g., because it was played
+// under other rules, or playing it again contradicts its claims.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Rejection {
+    Unverifiable(String),
+    Invalid(String),
+}
+
+// Implements displaying the reason of a rejection.
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rejection::Unverifiable(reason) => write!(f, "unverifiable ({})", reason),
+            Rejection::Invalid(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+// Checks that the run was played the way the table of its entry demands: with the preset tuning
+// of its difficulty unless it is custom, adaptive only in the adaptive table, and the day's
+// challenge for daily ones.
+fn check_table(entry: &Entry, replay: &Replay) -> Result<(), String> {
+    let preset = entry.difficulty.parse::<Difficulty>()?.gameplay();
+    let daily = match entry.mode.as_str() {
+        "custom" => return Ok(()),
+        "classic" | "adaptive" => None,
+        mode => match mode.strip_prefix("daily ") {
+            Some(date) => Some(date.parse::<Date>()?),
+            None => return Err(format!("unknown mode '{}'", mode)),
+        },
+    };
+    if replay.gameplay != preset {
+        return Err(format!(
+            "the tuning strays from the {} preset",
+            entry.difficulty
+        ));
+    }
+    if replay.adaptive != (entry.mode == "adaptive") {
+        return Err(format!(
+            "the adaptive setting strays from the {} mode",
+            entry.mode
+        ));
+    }
+    if let Some(date) = daily {
+        let seed = format!("{:016x}", daily::seed(date));
+        if replay.seed != seed
+            || !replay.scattered
+            || (replay.width, replay.height) != (DAILY_WIDTH, DAILY_HEIGHT)
+        {
+            return Err(format!("the game is not the challenge of {}", date));
+        }
+    }
+    Ok(())
+}
+
+// Plays the run of an entry again and confirms its table, score, wave, accuracy, and time;
+// returns the time played.
+pub fn verify(entry: &Entry) -> Result<Duration, Rejection> {
+    let replay = entry
+        .replay
+        .as_ref()
+        .ok_or_else(|| Rejection::Unverifiable("no replay".to_string()))?;
+    match replay.rules {
+        Some(RULES_VERSION) => {}
+        Some(rules) if rules > RULES_VERSION => {
+            return Err(Rejection::Unverifiable("newer rules".to_string()))
+        }
+        _ => return Err(Rejection::Unverifiable("older rules".to_string())),
+    }
+    check_table(entry, replay).map_err(Rejection::Invalid)?;
+    let game = replay.play().map_err(Rejection::Invalid)?;
+    let claimed = Duration::from_millis(replay.ticks * STEP_MILLIS);
+    if !game.over() || game.ticks() != replay.ticks {
+        return Err(Rejection::Invalid(format!(
+            "the game lasts {:.1}s instead of the claimed {:.1}s",
+            game.elapsed().as_secs_f64(),
+            claimed.as_secs_f64()
+        )));
+    }
+    let scorecard = game.scorecard;
+    if (scorecard.score, scorecard.wave, scorecard.accuracy())
+        != (entry.score, entry.wave, entry.accuracy)
+    {
+        return Err(Rejection::Invalid(format!(
+            "the replay scores {} in wave {} with {}% instead of the claimed {} in wave {} with {}%",
+            scorecard.score,
+            scorecard.wave,
+            scorecard.accuracy(),
+            entry.score,
+            entry.wave,
+            entry.accuracy
+        )));
+    }
+    Ok(claimed)
+}
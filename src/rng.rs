@@ -0,0 +1,55 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Provides a small pseudo-random number generator, so a seed replays the same game.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+// Implements the random number generator as xorshift64*.
+impl Rng {
+
+    // Creates a new generator from the given seed; every seed works, including 0.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: seed ^ 0x9e37_79b9_7f4a_7c15,
+        }
+        .warmed_up()
+    }
+
+    // Mixes the seed, so that similar seeds yield different sequences.
+    fn warmed_up(mut self) -> Self {
+        if self.state == 0 {
+            self.state = 0x2545_f491_4f6c_dd1d;
+        }
+        for _ in 0..4 {
+            self.next_u64();
+        }
+        self
+    }
+
+    // Returns the next number of the sequence.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // Returns a number below the given bound, which must not be 0.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
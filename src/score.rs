@@ -0,0 +1,62 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Provides the running score of a game.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Scorecard {
+    pub score: u64,
+    pub wave: u32,
+    pub fired: u32,
+    pub hits: u32,
+}
+
+// Implements the scorecard.
+impl Scorecard {
+
+    // Creates a new scorecard for the first wave.
+    pub fn new() -> Self {
+        Self {
+            wave: 1,
+            ..Self::default()
+        }
+    }
+
+    // Counts a fired shot.
+    pub fn record_shot(&mut self) {
+        self.fired += 1;
+    }
+
+    // Counts a shot that hit an invader, whether or not it killed it.
+    pub fn record_hit(&mut self) {
+        self.hits += 1;
+    }
+
+    // Counts a killed invader, which is worth more in later waves.
+    pub fn record_kill(&mut self) {
+        self.score += 10 * u64::from(self.wave);
+    }
+
+    // Moves on to the next wave.
+    pub fn next_wave(&mut self) {
+        self.wave += 1;
+    }
+
+    // Returns the share of shots that hit an invader, in percent.
+    pub fn accuracy(&self) -> u32 {
+        if self.fired == 0 {
+            return 0;
+        }
+        (u64::from(self.hits) * 100 / u64::from(self.fired)) as u32
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crossterm::style::{Color, SetBackgroundColor, SetForegroundColor};
+use crossterm::Command;
+
+use crate::backend::MemoryBackend;
+use crate::config::Theme;
+use crate::frame::{new_frame, Frame};
+use crate::render;
+
+// Provides the size of a single cell in SVG and HTML screenshots.
+const CELL_WIDTH: usize = 10;
+const CELL_HEIGHT: usize = 20;
+
+// Provides screenshots of a frame in several formats.
+pub trait Screenshot {
+
+    // Returns the frame as plain text with one line per row.
+    fn to_text(&self) -> String;
+
+    // Returns the frame as text colored with ANSI escape codes in the colors of the theme.
+    fn to_ansi(&self, theme: &Theme) -> String;
+
+    // Returns the frame as a self-contained SVG image on a monospace grid.
+    fn to_svg(&self) -> String;
+
+    // Returns the frame as a self-contained HTML page embedding the SVG image.
+    fn to_html(&self) -> String;
+
+    // Saves the frame in all formats next to the given path stem, e.g., `shot` as `shot.txt`.
+    fn save(&self, stem: &Path, theme: &Theme) -> io::Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        for (extension, contents) in [
+            ("txt", self.to_text()),
+            ("ans", self.to_ansi(theme)),
+            ("svg", self.to_svg()),
+            ("html", self.to_html()),
+        ] {
+            let path = stem.with_extension(extension);
+            fs::write(&path, contents)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+// Implements screenshots for frames.
+impl Screenshot for Frame {
+    fn to_text(&self) -> String {
+        grid(self).to_text()
+    }
+
+    fn to_ansi(&self, theme: &Theme) -> String {
+        let mut ansi = String::new();
+        for line in self.to_text().lines() {
+
+            // Paints every cell in the colors the renderer does: the background of the theme, and
+            // its foreground or the terminal's default.
+            let _ = SetBackgroundColor(theme.background).write_ansi(&mut ansi);
+            let foreground = theme.foreground.unwrap_or(Color::Reset);
+            let _ = SetForegroundColor(foreground).write_ansi(&mut ansi);
+            let _ = writeln!(ansi, "{}\x1b[0m", line);
+        }
+        ansi
+    }
+
+    fn to_svg(&self) -> String {
+        let text = self.to_text();
+        let width = self.len() * CELL_WIDTH;
+        let height = self.first().map_or(0, |col| col.len()) * CELL_HEIGHT;
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+            w = width,
+            h = height
+        );
+        let _ = writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"black\"/>");
+        let _ = writeln!(
+            svg,
+            "<g fill=\"#e5e5e5\" font-family=\"monospace\" font-size=\"{}\" text-anchor=\"middle\">",
+            CELL_HEIGHT * 4 / 5
+        );
+
+        // Places every character in its own cell to keep the grid intact.
+        for (y, line) in text.lines().enumerate() {
+            for (x, c) in line.chars().enumerate().filter(|(_, c)| *c != ' ') {
+                let _ = writeln!(
+                    svg,
+                    "<text x=\"{}\" y=\"{}\">{}</text>",
+                    x * CELL_WIDTH + CELL_WIDTH / 2,
+                    y * CELL_HEIGHT + CELL_HEIGHT * 4 / 5,
+                    escape(c)
+                );
+            }
+        }
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+
+    fn to_html(&self) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Terminal Invaders</title>\n</head>\n<body style=\"background: #202020; margin: 0; padding: 1em;\">\n{}</body>\n</html>\n",
+            self.to_svg()
+        )
+    }
+}
+
+// Renders the frame onto an in-memory grid like a terminal would display it.
+fn grid(frame: &Frame) -> MemoryBackend {
+    let dimensions = [
+        frame.len() as u16,
+        frame.first().map_or(0, |col| col.len()) as u16,
+    ];
+    let mut grid = MemoryBackend::new(&dimensions);
+
+    // Draws over a blank frame so blanks never cut off longer strings, e.g., overlay lines.
+    render::render(&mut grid, &new_frame(&dimensions), frame, false);
+    grid
+}
+
+// Escapes a character for XML.
+fn escape(c: char) -> String {
+    match c {
+        '&' => "&amp;".to_string(),
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '"' => "&quot;".to_string(),
+        c => c.to_string(),
+    }
+}
@@ -16,44 +16,66 @@ use std::time::Duration;
 
 use rusty_time::prelude::Timer;
 
-use crate::frame::{Drawable, Frame};
+use crate::{
+    config::{Gameplay, Theme},
+    frame::{Drawable, Frame},
+    weapon::Weapon,
+};
 
 // Provides a struct for shots with coordinates and explosion attributes.
 pub struct Shot {
     pub x: u16,
     pub y: u16,
+    pub from_y: u16,
     pub exploding: bool,
+    pub hit: bool,
+    pub piercing: bool,
+    pub damage: u32,
+    pub weapon: Weapon,
     timer: Timer,
+    explosion_millis: u64,
 }
 
 // Implements the shot struct.
 impl Shot {
 
-    // Creates a new shot at the given position.
-    pub fn new(x: u16, y: u16) -> Self {
+    // Creates a new shot at the given position, timed by the gameplay.
+    pub fn new(x: u16, y: u16, gameplay: &Gameplay) -> Self {
         Self {
 
             // Fires the shot at x, y.
             x,
             y,
+            from_y: y,
 
-            // It's not exploding, yet.
+            // It's not exploding, and has not hit anything, yet, and stops at the first invader it
+            // hits, fired by the blaster unless told otherwise.
             exploding: false,
+            hit: false,
+            piercing: false,
+            damage: 1,
+            weapon: Weapon::Blaster,
 
-            // It moves with a speed of 1/50ms.
-            timer: Timer::from_millis(50),
+            // It moves with a speed of 1/50ms by default.
+            timer: Timer::from_millis(gameplay.shot_millis),
+
+            // It explodes for 250ms by default.
+            explosion_millis: gameplay.explosion_millis,
         }
     }
 
-    // Updates the shot's positions.
+    // Updates the shot's positions, remembering the row it set out from, so that hits along the
+    // way count.
     pub fn update(&mut self, delta: Duration) {
+        self.from_y = self.y;
+        let overshoot = delta.saturating_sub(self.timer.time_left);
         self.timer.update(delta);
 
-        // Only moves the shot if the timer is ready.
+        // Only moves the shot if the timer is ready, by more than a cell if it is that fast.
         if self.timer.ready && !self.exploding {
-            if self.y > 0 {
-                self.y -= 1;
-            }
+            let cells = 1 + overshoot.as_nanos() / self.timer.duration.as_nanos().max(1);
+            let cells = cells.min(u128::from(u16::MAX)) as u16;
+            self.y = self.y.saturating_sub(cells);
             self.timer.reset();
         }
     }
@@ -63,14 +85,14 @@ impl Shot {
         self.exploding = true;
 
         // Extends explosion time.
-        self.timer = Timer::from_millis(250);
+        self.timer = Timer::from_millis(self.explosion_millis);
     }
 
     // Determines whether a shot died.
     pub fn died(&self) -> bool {
 
         // Is it out of bounds?
-        let out_of_bounds = self.y <= 0;
+        let out_of_bounds = self.y == 0;
 
         // Did it explode?
         let did_explode = self.exploding && self.timer.ready;
@@ -82,10 +104,14 @@ impl Shot {
 impl Drawable for Shot {
 
     // Draws a shot on a given frame.
-    fn draw(&self, frame: &mut Frame) {
-
-        // Animates explosions.
-        let shot = if self.exploding { "◉" } else { "◦" };
+    fn draw(&self, frame: &mut Frame, theme: &Theme) {
+
+        // Animates explosions, and tells lasers apart.
+        let shot = match (self.exploding, self.piercing) {
+            (true, _) => theme.explosion.as_str(),
+            (false, true) => "│",
+            (false, false) => theme.shot.as_str(),
+        };
         frame[self.x as usize][self.y as usize] = shot.to_string();
     }
 }
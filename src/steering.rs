@@ -0,0 +1,120 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use rusty_time::prelude::Timer;
+
+use crate::{config::Gameplay, keys::Action, player::Player, MOVE_MILLIS};
+
+// Provides the steering of the player's ship from movement keys or the mouse.
+pub struct Steering {
+    held: Vec<Action>,
+    target: Option<u16>,
+    timer: Timer,
+    continuous: bool,
+}
+
+// Implements the steering.
+impl Steering {
+
+    // Creates a new steering; continuous steering requires key-release events from the terminal.
+    pub fn new(continuous: bool) -> Self {
+        Self {
+            held: Vec::new(),
+            target: None,
+
+            // Moves one cell per interval while a key is held.
+            timer: Timer::from_millis(MOVE_MILLIS),
+            continuous,
+        }
+    }
+
+    // Applies the tuning to the ship's speed.
+    pub fn tune(&mut self, gameplay: &Gameplay) {
+        self.timer = Timer::from_millis(gameplay.move_millis);
+    }
+
+    // Determines whether the ship moves for as long as a key is held.
+    pub fn continuous(&self) -> bool {
+        self.continuous
+    }
+
+    // Handles a pressed movement key by moving the ship one cell right away.
+    pub fn press(&mut self, action: Action, player: &mut Player) {
+
+        // The keyboard takes over from the mouse.
+        self.target = None;
+        step(action, player);
+        if self.continuous && !self.held.contains(&action) {
+            self.held.push(action);
+            self.timer.reset();
+        }
+    }
+
+    // Handles a released movement key.
+    pub fn release(&mut self, action: Action) {
+        self.held.retain(|held| *held != action);
+    }
+
+    // Lets the ship follow the mouse to the given column.
+    pub fn aim(&mut self, column: u16) {
+        if self.target.is_none() {
+            self.timer.reset();
+        }
+        self.target = Some(column);
+    }
+
+    // Releases all keys and stops following the mouse, e.g., when the release events might get
+    // lost or the mouse was let go of.
+    pub fn release_all(&mut self) {
+        self.held.clear();
+        self.target = None;
+    }
+
+    // Keeps moving the ship while keys are held or until it reached the mouse.
+    pub fn update(&mut self, delta: Duration, player: &mut Player) {
+        if self.held.is_empty() && self.target.is_none() {
+            return;
+        }
+        self.timer.update(delta);
+        if self.timer.ready {
+            self.timer.reset();
+            for action in self.held.iter() {
+                step(*action, player);
+            }
+            if let Some(target) = self.target {
+                let (x, _) = player.position();
+                if target < x {
+                    player.move_left();
+                } else if target > x {
+                    player.move_right();
+                } else {
+                    self.target = None;
+                }
+            }
+        }
+    }
+}
+
+// Moves the ship by one cell for the given movement action.
+fn step(action: Action, player: &mut Player) {
+    match action {
+        Action::MoveLeft => player.move_left(),
+        Action::MoveRight => player.move_right(),
+        Action::MoveUp => player.move_up(),
+        Action::MoveDown => player.move_down(),
+        _ => {}
+    }
+}
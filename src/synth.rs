@@ -0,0 +1,310 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::f32::consts::TAU;
+
+use serde::Deserialize;
+
+// Provides the sample rate of all synthesized sounds.
+pub const SAMPLE_RATE: u32 = 22_050;
+
+// Provides the limits of configured sounds: the length of a sound effect in seconds, and the
+// pitches in hertz, up to the highest one the sample rate can carry.
+pub const MAX_SECONDS: f32 = 5.0;
+pub const MAX_HZ: f32 = SAMPLE_RATE as f32 / 2.0;
+
+// Provides the basic shapes of an oscillator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Waveform {
+    Sine,
+    Square,
+    Triangle,
+    Sawtooth,
+    Noise,
+}
+
+// Provides an attack-decay-sustain-release envelope; times in seconds, sustain as level.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Envelope {
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub release: f32,
+}
+
+// Implements the envelope.
+impl Envelope {
+
+    // Creates a percussive envelope that starts loud and fades out.
+    pub fn pluck() -> Self {
+        Self {
+            attack: 0.002,
+            decay: 0.0,
+            sustain: 1.0,
+            release: 1.0,
+        }
+    }
+
+    // Creates an envelope that holds the level and only fades at the very end.
+    pub fn hold() -> Self {
+        Self {
+            attack: 0.005,
+            decay: 0.0,
+            sustain: 1.0,
+            release: 0.02,
+        }
+    }
+
+    // Returns the level at the given time of a tone lasting for the given duration.
+    fn level(&self, time: f32, duration: f32) -> f32 {
+
+        // The release is relative to the duration if it exceeds it, e.g., for plucks.
+        let release = self.release.min(duration);
+        let level = if time < self.attack {
+            time / self.attack
+        } else if time < self.attack + self.decay {
+            1.0 - (1.0 - self.sustain) * (time - self.attack) / self.decay
+        } else {
+            self.sustain
+        };
+        let left = duration - time;
+        if left < release {
+            level * left / release
+        } else {
+            level
+        }
+    }
+}
+
+// Implements a percussive envelope by default.
+impl Default for Envelope {
+    fn default() -> Self {
+        Self::pluck()
+    }
+}
+
+// Provides a single tone with a pitch sweep and an optional warble.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tone {
+    pub waveform: Waveform,
+    pub start_hz: f32,
+    pub end_hz: f32,
+    pub seconds: f32,
+    pub volume: f32,
+    #[serde(default)]
+    pub envelope: Envelope,
+    #[serde(default)]
+    pub warble_hz: f32,
+    #[serde(default)]
+    pub warble_depth: f32,
+}
+
+// Implements the tone.
+impl Tone {
+
+    // Creates a new tone sweeping between two pitches with a percussive envelope.
+    pub fn new(waveform: Waveform, start_hz: f32, end_hz: f32, seconds: f32, volume: f32) -> Self {
+        Self {
+            waveform,
+            start_hz,
+            end_hz,
+            seconds,
+            volume,
+            envelope: Envelope::pluck(),
+            warble_hz: 0.0,
+            warble_depth: 0.0,
+        }
+    }
+
+    // Replaces the envelope of the tone.
+    pub fn with_envelope(mut self, envelope: Envelope) -> Self {
+        self.envelope = envelope;
+        self
+    }
+
+    // Adds a warble modulating the pitch by the given depth as a fraction of the pitch.
+    pub fn with_warble(mut self, warble_hz: f32, warble_depth: f32) -> Self {
+        self.warble_hz = warble_hz;
+        self.warble_depth = warble_depth;
+        self
+    }
+
+    // Checks that the tone is audible, lasts at least a sample, and stays within the limits.
+    fn validate(&self) -> Result<(), String> {
+        if !(1.0 / SAMPLE_RATE as f32..=MAX_SECONDS).contains(&self.seconds) {
+            return Err(format!(
+                "seconds must be between {} and {}, got {}",
+                1.0 / SAMPLE_RATE as f32,
+                MAX_SECONDS,
+                self.seconds
+            ));
+        }
+        for (name, value, max) in [
+            ("start_hz", self.start_hz, MAX_HZ),
+            ("end_hz", self.end_hz, MAX_HZ),
+            ("warble_hz", self.warble_hz, MAX_HZ),
+            ("volume", self.volume, 1.0),
+            ("warble_depth", self.warble_depth, 1.0),
+            ("envelope.attack", self.envelope.attack, MAX_SECONDS),
+            ("envelope.decay", self.envelope.decay, MAX_SECONDS),
+            ("envelope.sustain", self.envelope.sustain, 1.0),
+            ("envelope.release", self.envelope.release, MAX_SECONDS),
+        ] {
+            if !(0.0..=max).contains(&value) {
+                return Err(format!(
+                    "{} must be between 0 and {}, got {}",
+                    name, max, value
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Appends the samples of the tone to the given buffer.
+    fn render(&self, samples: &mut Vec<f32>, noise: &mut Noise) {
+        let count = (self.seconds * SAMPLE_RATE as f32) as usize;
+        let mut phase = 0_f32;
+        for i in 0..count {
+            let time = i as f32 / SAMPLE_RATE as f32;
+            let progress = i as f32 / count as f32;
+            let warble = 1.0 + self.warble_depth * (TAU * self.warble_hz * time).sin();
+            let pitch = (self.start_hz + (self.end_hz - self.start_hz) * progress) * warble;
+            phase = (phase + pitch / SAMPLE_RATE as f32).fract();
+            let value = match self.waveform {
+                Waveform::Sine => (TAU * phase).sin(),
+                Waveform::Square => {
+                    if phase < 0.5 {
+                        1.0
+                    } else {
+                        -1.0
+                    }
+                }
+                Waveform::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+                Waveform::Sawtooth => 2.0 * phase - 1.0,
+                Waveform::Noise => noise.next(),
+            };
+            samples.push(value * self.volume * self.envelope.level(time, self.seconds));
+        }
+    }
+}
+
+// Provides a sound effect as a sequence of tones, some of them layered.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Patch {
+    pub layers: Vec<Vec<Tone>>,
+}
+
+// Implements the patch.
+impl Patch {
+
+    // Creates a patch playing the given tones one after another.
+    pub fn sequence(tones: Vec<Tone>) -> Self {
+        Self {
+            layers: vec![tones],
+        }
+    }
+
+    // Creates a patch playing the given sequences at the same time.
+    pub fn layered(layers: Vec<Vec<Tone>>) -> Self {
+        Self { layers }
+    }
+
+    // Checks that the patch plays at least one tone, and that no layer of it runs too long.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.layers.iter().all(|layer| layer.is_empty()) {
+            return Err("must play at least one tone".to_string());
+        }
+        for layer in self.layers.iter() {
+            for tone in layer.iter() {
+                tone.validate()?;
+            }
+            let seconds: f32 = layer.iter().map(|tone| tone.seconds).sum();
+            if seconds > MAX_SECONDS {
+                return Err(format!(
+                    "a layer must not last longer than {} seconds, got {}",
+                    MAX_SECONDS, seconds
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Synthesizes the patch into samples between -1 and 1.
+    pub fn render(&self) -> Vec<f32> {
+        let mut noise = Noise::new();
+        let mut mix: Vec<f32> = Vec::new();
+        for layer in self.layers.iter() {
+            let mut samples = Vec::new();
+            for tone in layer.iter() {
+                tone.render(&mut samples, &mut noise);
+            }
+            if samples.len() > mix.len() {
+                mix.resize(samples.len(), 0.0);
+            }
+            for (mixed, sample) in mix.iter_mut().zip(samples) {
+                *mixed += sample;
+            }
+        }
+        for sample in mix.iter_mut() {
+            *sample = sample.clamp(-1.0, 1.0);
+        }
+        mix
+    }
+
+    // Synthesizes the patch into a 16-bit mono WAV file.
+    pub fn to_wav(&self) -> Vec<u8> {
+        let samples = self.render();
+        let data_len = (samples.len() * 2) as u32;
+        let mut wav = Vec::with_capacity(44 + data_len as usize);
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16_u32.to_le_bytes());
+        wav.extend_from_slice(&1_u16.to_le_bytes());
+        wav.extend_from_slice(&1_u16.to_le_bytes());
+        wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+        wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+        wav.extend_from_slice(&2_u16.to_le_bytes());
+        wav.extend_from_slice(&16_u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        for sample in samples {
+            wav.extend_from_slice(&((sample * i16::MAX as f32) as i16).to_le_bytes());
+        }
+        wav
+    }
+}
+
+// Provides a small deterministic noise generator so sounds are identical on every start.
+struct Noise {
+    state: u32,
+}
+
+// Implements the noise generator as xorshift.
+impl Noise {
+    fn new() -> Self {
+        Self { state: 0x2545_f491 }
+    }
+
+    fn next(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+}
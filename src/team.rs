@@ -0,0 +1,264 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    leaderboard::{self, Entry},
+    replay::{self, Rejection},
+};
+
+// Provides the version of the team records this build reads and writes; records of version 1
+// were written before runs kept a replay.
+pub const RECORD_VERSION: u32 = 2;
+
+// Provides a score record of a single run, as stored in the team directory.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Record {
+    pub version: u32,
+
+    // Keeps the seed as hex, since TOML integers stop at i64.
+    pub seed: String,
+    pub checksum: String,
+    pub entry: Entry,
+}
+
+// Implements the team record.
+impl Record {
+
+    // Creates a new signed record of the run with the given seed.
+    pub fn new(entry: Entry, seed: u64) -> Self {
+        let mut record = Self {
+            version: RECORD_VERSION,
+            seed: format!("{:016x}", seed),
+            checksum: String::new(),
+            entry,
+        };
+        record.checksum = record.expected_checksum();
+        record
+    }
+
+    // Determines whether the record is unchanged since it was signed.
+    pub fn is_valid(&self) -> bool {
+        self.version <= RECORD_VERSION && self.checksum == self.expected_checksum()
+    }
+
+    // Plays the run again to confirm the score; returns the time played.
+    pub fn verify(&self) -> Result<Duration, Rejection> {
+        if !self.is_valid() {
+            return Err(Rejection::Invalid(
+                "the checksum does not match".to_string(),
+            ));
+        }
+        // Records from before runs kept a replay have none to check against.
+        let seed = self.entry.replay.as_ref().map(|replay| &replay.seed);
+        if seed.is_some_and(|seed| *seed != self.seed) {
+            return Err(Rejection::Invalid(
+                "the replay is of another run".to_string(),
+            ));
+        }
+        replay::verify(&self.entry)
+    }
+
+    // Computes the checksum over all fields; catches edited records, though not anyone who
+    // recomputes it with this very code.
+    fn expected_checksum(&self) -> String {
+        let entry = &self.entry;
+        let mut fields = vec![
+            self.version.to_string(),
+            self.seed.clone(),
+            entry.name.clone(),
+            entry.score.to_string(),
+            entry.wave.to_string(),
+            entry.accuracy.to_string(),
+            entry.date.clone(),
+            entry.mode.clone(),
+            entry.difficulty.clone(),
+        ];
+        if let Some(replay) = &entry.replay {
+            fields.push(toml::to_string(replay).unwrap_or_default());
+        }
+
+        // Prefixes every field with its length, so that no two records hash the same text.
+        let mut hash = fnv1a(0xcbf2_9ce4_8422_2325, b"terminal_invaders");
+        for field in fields {
+            hash = fnv1a(hash, format!("{}:{}", field.len(), field).as_bytes());
+        }
+        format!("{:016x}", hash)
+    }
+}
+
+// Reads a record file; returns none if it is unreadable.
+pub fn read(path: &Path) -> Option<Record> {
+    let contents = fs::read_to_string(path).ok()?;
+    toml::from_str(&contents).ok()
+}
+
+// Hashes the bytes with 64-bit FNV-1a, continuing from the given hash.
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+// Provides a record file left out of the leaderboard, with the reason.
+pub type Rejected = (PathBuf, Rejection);
+
+// Provides the contents of the local file remembering the checksums of team records whose runs
+// held up when played again.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Verified {
+    checksums: BTreeSet<String>,
+}
+
+// Provides a leaderboard shared by a team through a directory, e.g., on NFS, where every run
+// writes a file of its own, so that no locking is needed.
+pub struct TeamLeaderboard {
+    dir: PathBuf,
+    cache: Option<PathBuf>,
+}
+
+// Implements the team leaderboard.
+impl TeamLeaderboard {
+
+    // Creates a team leaderboard in the given directory.
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir, cache: None }
+    }
+
+    // Remembers the records that held up in the given local file, so that their runs are only
+    // played again once.
+    pub fn with_cache(mut self, path: PathBuf) -> Self {
+        self.cache = Some(path);
+        self
+    }
+
+    // Returns the directory of the team leaderboard.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // Writes the record of a run into a new file and returns its path.
+    pub fn submit(&self, entry: Entry, seed: u64) -> io::Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+        let record = Record::new(entry, seed);
+        let contents = toml::to_string(&record)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        let name = format!(
+            "{}-{}-{}.toml",
+            record.entry.date, record.entry.name, record.seed
+        );
+        let path = self.dir.join(&name);
+
+        // Writes under a hidden name first, so that other players never read half of a record,
+        // then links it into place, which never replaces a record of the same name, not even
+        // one written at the same time.
+        let temporary = self.dir.join(format!(".{}.tmp", name));
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temporary)?
+            .write_all(contents.as_bytes())?;
+        let linked = fs::hard_link(&temporary, &path);
+        let _ = fs::remove_file(&temporary);
+        match linked {
+            Ok(()) => Ok(path),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("the record {} exists already", path.display()),
+            )),
+            Err(error) => Err(error),
+        }
+    }
+
+    // Loads the entries of all records whose runs hold up when played again; returns the files
+    // that do not apart, with the reason. A missing directory is an empty leaderboard.
+    pub fn load(&self) -> io::Result<(Vec<Entry>, Vec<Rejected>)> {
+        let mut entries = Vec::new();
+        let mut rejected = Vec::new();
+        let files = match fs::read_dir(&self.dir) {
+            Ok(files) => files,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Ok((entries, rejected))
+            }
+            Err(error) => return Err(error),
+        };
+
+        // Plays only the runs again that did not hold up before, since that takes a while; the
+        // checksum covers the whole record, replay included.
+        let mut verified = self.verified();
+        let known = verified.checksums.len();
+        for file in files {
+            let path = file?.path();
+            if path.extension().is_none_or(|extension| extension != "toml") {
+                continue;
+            }
+            let Some(record) = read(&path) else {
+                rejected.push((path, Rejection::Invalid("unreadable".to_string())));
+                continue;
+            };
+            if !(record.is_valid() && verified.checksums.contains(&record.checksum)) {
+                if let Err(rejection) = record.verify() {
+                    rejected.push((path, rejection));
+                    continue;
+                }
+                verified.checksums.insert(record.checksum);
+            }
+            entries.push(record.entry);
+        }
+        if verified.checksums.len() != known {
+            self.remember(&verified);
+        }
+        rejected.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok((entries, rejected))
+    }
+
+    // Reads the checksums of the records that held up before; a missing or corrupted cache is
+    // empty.
+    fn verified(&self) -> Verified {
+        self.cache
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| toml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    // Writes the checksums of the records that held up; the cache only saves time, so failing
+    // to write it is no error.
+    fn remember(&self, verified: &Verified) {
+        let Some(path) = &self.cache else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(contents) = toml::to_string(verified) {
+            let _ = fs::write(path, contents);
+        }
+    }
+}
+
+// Returns the default location of the cache of records that held up, next to the leaderboard.
+pub fn default_cache_path() -> Option<PathBuf> {
+    leaderboard::default_path().map(|path| path.with_file_name("verified.toml"))
+}
@@ -0,0 +1,234 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use crate::{config::Gameplay, COOLDOWN_MILLIS};
+
+// Provides the weapons the ship can fire.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Weapon {
+    Blaster,
+    Rapid,
+    Spread,
+    Laser,
+}
+
+// Provides what a weapon fires, and how often.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Spec {
+
+    // The time between two volleys, and the time a shot takes to move one cell.
+    pub cooldown_millis: u64,
+    pub shot_millis: u64,
+
+    // The columns a volley fires at, relative to the ship.
+    pub spread: &'static [i16],
+
+    // Whether shots fly on through the invaders they kill, and how much health they take.
+    pub piercing: bool,
+    pub damage: u32,
+
+    // The volleys a weapon comes with when it is handed out for keeps, none for unlimited ones,
+    // and the number of its shots that may be in the air at once. Power-ups hand weapons out
+    // for their duration instead.
+    pub ammo: Option<u32>,
+    pub cap: usize,
+}
+
+// Implements the weapons.
+impl Weapon {
+
+    // Lists all weapons.
+    pub const ALL: [Weapon; 4] = [
+        Weapon::Blaster,
+        Weapon::Rapid,
+        Weapon::Spread,
+        Weapon::Laser,
+    ];
+
+    // Returns the name of the weapon as shown on the heads-up display.
+    pub fn name(self) -> &'static str {
+        match self {
+            Weapon::Blaster => "blaster",
+            Weapon::Rapid => "rapid",
+            Weapon::Spread => "spread",
+            Weapon::Laser => "laser",
+        }
+    }
+
+    // Returns the definition of the weapon, based on the tuning of the blaster.
+    pub fn spec(self, gameplay: &Gameplay) -> Spec {
+        let blaster = Spec {
+            cooldown_millis: COOLDOWN_MILLIS,
+            shot_millis: gameplay.shot_millis,
+            spread: &[0],
+            piercing: false,
+            damage: 1,
+            ammo: None,
+            cap: gameplay.shots,
+        };
+        match self {
+            Weapon::Blaster => blaster,
+
+            // Fires four times as often, and twice as many shots that fly twice as fast.
+            Weapon::Rapid => Spec {
+                cooldown_millis: COOLDOWN_MILLIS / 4,
+                shot_millis: (gameplay.shot_millis / 2).max(1),
+                ammo: Some(60),
+                cap: gameplay.shots * 2,
+                ..blaster
+            },
+
+            // Fans out to the neighboring columns, but takes longer to reload.
+            Weapon::Spread => Spec {
+                cooldown_millis: COOLDOWN_MILLIS * 3 / 2,
+                spread: &[-1, 0, 1],
+                ammo: Some(30),
+                cap: gameplay.shots * 3,
+                ..blaster
+            },
+
+            // Burns through whole columns, but takes a while to charge.
+            Weapon::Laser => Spec {
+                cooldown_millis: COOLDOWN_MILLIS * 2,
+                piercing: true,
+                damage: 2,
+                ammo: Some(20),
+                ..blaster
+            },
+        }
+    }
+}
+
+// Provides a weapon the ship carries, with its remaining volleys and cooldown.
+struct Slot {
+    weapon: Weapon,
+    ammo: Option<u32>,
+    cooldown: Duration,
+}
+
+// Provides the weapons the ship carries; the first one never runs out of ammo.
+pub struct Arsenal {
+    slots: Vec<Slot>,
+    selected: usize,
+}
+
+// Implements the arsenal.
+impl Arsenal {
+
+    // Creates a new arsenal with the given weapon to fall back on.
+    pub fn new(weapon: Weapon) -> Self {
+        Self {
+            slots: vec![Slot {
+                weapon,
+                ammo: None,
+                cooldown: Duration::ZERO,
+            }],
+            selected: 0,
+        }
+    }
+
+    // Adds the weapon with the given ammo, or refills it if carried already, and selects it.
+    pub fn arm(&mut self, weapon: Weapon, ammo: Option<u32>) {
+        match self.slots.iter().position(|slot| slot.weapon == weapon) {
+            Some(idx) => {
+                if self.slots[idx].ammo.is_some() {
+                    self.slots[idx].ammo = ammo;
+                }
+                self.selected = idx;
+            }
+            None => {
+                self.slots.push(Slot {
+                    weapon,
+                    ammo,
+                    cooldown: Duration::ZERO,
+                });
+                self.selected = self.slots.len() - 1;
+            }
+        }
+    }
+
+    // Puts the weapon away, e.g., once the power-up that handed it out ran out, and selects the
+    // first weapon if it was selected; the first weapon is never put away.
+    pub fn disarm(&mut self, weapon: Weapon) {
+        let Some(idx) = self
+            .slots
+            .iter()
+            .skip(1)
+            .position(|slot| slot.weapon == weapon)
+        else {
+            return;
+        };
+        let idx = idx + 1;
+        self.slots.remove(idx);
+        if self.selected == idx {
+            self.selected = 0;
+        } else if self.selected > idx {
+            self.selected -= 1;
+        }
+    }
+
+    // Returns the weapons carried, in the order they are cycled through.
+    pub fn weapons(&self) -> Vec<Weapon> {
+        self.slots.iter().map(|slot| slot.weapon).collect()
+    }
+
+    // Returns the selected weapon.
+    pub fn weapon(&self) -> Weapon {
+        self.slots[self.selected].weapon
+    }
+
+    // Returns the volleys left in the selected weapon, none if unlimited.
+    pub fn ammo(&self) -> Option<u32> {
+        self.slots[self.selected].ammo
+    }
+
+    // Selects the next weapon carried.
+    pub fn cycle(&mut self) {
+        self.selected = (self.selected + 1) % self.slots.len();
+    }
+
+    // Determines whether the selected weapon has cooled down.
+    pub fn ready(&self) -> bool {
+        self.slots[self.selected].cooldown.is_zero()
+    }
+
+    // Spends a volley of the selected weapon; drops the weapon once it runs out of ammo.
+    pub fn fire(&mut self, spec: &Spec) {
+        let slot = &mut self.slots[self.selected];
+        slot.cooldown = Duration::from_millis(spec.cooldown_millis);
+        if let Some(ammo) = slot.ammo.as_mut() {
+            *ammo = ammo.saturating_sub(1);
+            if *ammo == 0 {
+                self.slots.remove(self.selected);
+                self.selected = 0;
+            }
+        }
+    }
+
+    // Cools down all weapons.
+    pub fn update(&mut self, delta: Duration) {
+        for slot in self.slots.iter_mut() {
+            slot.cooldown = slot.cooldown.saturating_sub(delta);
+        }
+    }
+}
+
+// Implements an arsenal with just the blaster.
+impl Default for Arsenal {
+    fn default() -> Self {
+        Self::new(Weapon::Blaster)
+    }
+}
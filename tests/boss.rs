@@ -0,0 +1,86 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::thread;
+use std::time::Duration;
+
+use terminal_invaders::boss::BossScreen;
+use terminal_invaders::config::Theme;
+use terminal_invaders::frame::{new_frame, Drawable};
+
+// Returns the given row of the frame as text.
+fn row(frame: &[Vec<String>], y: usize) -> String {
+    frame.iter().map(|col| col[y].as_str()).collect()
+}
+
+#[test]
+fn hides_game_behind_fake_build() {
+    let mut boss = BossScreen::new(&[40, 8]);
+    boss.update(Duration::from_secs(1));
+    assert!(boss.lines().is_empty());
+
+    // The build has been going on for a while once the boss screen shows up.
+    boss.toggle();
+    boss.update(Duration::from_millis(1));
+    let mut frame = new_frame(&[40, 8]);
+    frame[0][7] = "△".to_string();
+    boss.draw(&mut frame, &Theme::default());
+    assert!(row(&frame, 7).starts_with("   Compiling "));
+    assert!(frame.iter().flatten().all(|cell| cell != "△"));
+
+    // It keeps compiling and eventually finishes, keeping no more lines than fit on the screen.
+    let mut finished = false;
+    for _ in 0..200 {
+        boss.update(Duration::from_millis(700));
+        let lines = boss.lines();
+        assert!(lines.len() <= 8);
+        finished |= lines
+            .iter()
+            .any(|line| line.starts_with("    Finished `dev` profile"));
+    }
+    assert!(finished);
+}
+
+#[cfg(unix)]
+#[test]
+fn shows_output_of_wrapped_command() {
+    let mut boss = BossScreen::wrap("echo compiling; echo failed >&2", &[40, 8]).unwrap();
+    for _ in 0..100 {
+        if boss.lines().len() == 2 {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    let mut lines = boss.lines();
+    lines.sort();
+    assert_eq!(lines, ["compiling", "failed"]);
+
+    // The real output is never mixed with the fake one.
+    boss.toggle();
+    boss.update(Duration::from_secs(1));
+    assert_eq!(boss.lines().len(), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn keeps_end_of_long_output() {
+    let boss = BossScreen::wrap("seq 1 1000", &[40, 3]).unwrap();
+    for _ in 0..100 {
+        if boss.lines().last().map(String::as_str) == Some("1000") {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(boss.lines(), ["998", "999", "1000"]);
+}
@@ -0,0 +1,174 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use terminal_invaders::cast::{CastRecorder, CastWriter};
+
+// Provides the JSON values an asciicast file is made of.
+#[derive(Debug, PartialEq)]
+enum Json {
+    Number(f64),
+    Text(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+// Parses a JSON value from the start of the characters, strictly enough to catch bad escapes.
+fn parse(chars: &mut std::iter::Peekable<std::str::Chars>) -> Json {
+    while chars.peek() == Some(&' ') {
+        chars.next();
+    }
+    match chars.next().expect("A value should follow.") {
+        '"' => {
+            let mut text = String::new();
+            loop {
+                match chars.next().expect("The string should be closed.") {
+                    '"' => return Json::Text(text),
+                    '\\' => match chars.next().expect("The escape should be complete.") {
+                        '"' => text.push('"'),
+                        '\\' => text.push('\\'),
+                        'n' => text.push('\n'),
+                        'r' => text.push('\r'),
+                        't' => text.push('\t'),
+                        'u' => {
+                            let hex: String = chars.by_ref().take(4).collect();
+                            let code = u32::from_str_radix(&hex, 16).unwrap();
+                            text.push(char::from_u32(code).unwrap());
+                        }
+                        c => panic!("unexpected escape '\\{}'", c),
+                    },
+                    c => {
+                        assert!(!c.is_control(), "unescaped control character {:?}", c);
+                        text.push(c);
+                    }
+                }
+            }
+        }
+        '[' => {
+            let mut items = Vec::new();
+            loop {
+                items.push(parse(chars));
+                match chars.next() {
+                    Some(',') => {}
+                    Some(']') => return Json::Array(items),
+                    c => panic!("unexpected {:?} in array", c),
+                }
+            }
+        }
+        '{' => {
+            let mut fields = Vec::new();
+            loop {
+                let Json::Text(key) = parse(chars) else {
+                    panic!("keys should be strings");
+                };
+                assert_eq!(chars.next(), Some(':'));
+                fields.push((key, parse(chars)));
+                match chars.next() {
+                    Some(',') => {}
+                    Some('}') => return Json::Object(fields),
+                    c => panic!("unexpected {:?} in object", c),
+                }
+            }
+        }
+        c => {
+            let mut number = c.to_string();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                number.push(c);
+            }
+            Json::Number(number.parse().unwrap())
+        }
+    }
+}
+
+// Parses every line of the recording as a JSON value.
+fn parse_lines(out: &[u8]) -> Vec<Json> {
+    String::from_utf8(out.to_vec())
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let mut chars = line.chars().peekable();
+            let value = parse(&mut chars);
+            assert_eq!(chars.next(), None, "trailing characters in {}", line);
+            value
+        })
+        .collect()
+}
+
+// Returns the code and data of an event.
+fn event(value: &Json) -> (&str, &str) {
+    match value {
+        Json::Array(items) => match items.as_slice() {
+            [Json::Number(_), Json::Text(code), Json::Text(data)] => (code, data),
+            _ => panic!("unexpected event {:?}", value),
+        },
+        _ => panic!("unexpected event {:?}", value),
+    }
+}
+
+#[test]
+fn records_header_and_events() {
+    let mut out = Vec::new();
+    {
+        let mut recorder = CastRecorder::new(&mut out, &[80, 24]).unwrap();
+        recorder.output(b"\x1b[2J").unwrap();
+        recorder.output(b"").unwrap();
+        recorder.resize(100, 30).unwrap();
+    }
+    let lines = parse_lines(&out);
+    assert_eq!(lines.len(), 3);
+    let Json::Object(header) = &lines[0] else {
+        panic!("the header should be an object");
+    };
+    assert!(header.contains(&("version".to_string(), Json::Number(2.0))));
+    assert!(header.contains(&("width".to_string(), Json::Number(80.0))));
+    assert!(header.contains(&("height".to_string(), Json::Number(24.0))));
+    assert_eq!(event(&lines[1]), ("o", "\x1b[2J"));
+    assert_eq!(event(&lines[2]), ("r", "100x30"));
+}
+
+#[test]
+fn escapes_output() {
+    let output = "say \"hi\"\\ \u{1b}[1;31m\u{7}\u{0}\r\n\ttabs ■ \u{7f}";
+    let mut out = Vec::new();
+    {
+        let mut recorder = CastRecorder::new(&mut out, &[80, 24]).unwrap();
+        recorder.output(output.as_bytes()).unwrap();
+    }
+    let text = String::from_utf8(out.clone()).unwrap();
+    assert!(text.contains("\\u001b[1;31m\\u0007\\u0000\\r\\n\\t"));
+    assert!(text.contains("say \\\"hi\\\"\\\\ "));
+    assert_eq!(event(&parse_lines(&out)[1]), ("o", output));
+}
+
+#[test]
+fn records_one_event_per_flush() {
+    let mut out = Vec::new();
+    let mut terminal = Vec::new();
+    {
+        let recorder = CastRecorder::new(&mut out, &[80, 24]).unwrap();
+        let mut writer = CastWriter::new(&mut terminal, Arc::new(Mutex::new(recorder)));
+        write!(writer, "ab").unwrap();
+        write!(writer, "c").unwrap();
+        writer.flush().unwrap();
+        writer.flush().unwrap();
+        write!(writer, "d").unwrap();
+        writer.flush().unwrap();
+    }
+    assert_eq!(terminal, b"abcd");
+    let lines = parse_lines(&out);
+    let events: Vec<(&str, &str)> = lines[1..].iter().map(event).collect();
+    assert_eq!(events, [("o", "abc"), ("o", "d")]);
+}
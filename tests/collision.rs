@@ -0,0 +1,165 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use terminal_invaders::config::Gameplay;
+use terminal_invaders::invaders::Invaders;
+use terminal_invaders::player::Player;
+use terminal_invaders::weapon::Weapon;
+use terminal_invaders::{COOLDOWN_MILLIS, STEP_MILLIS};
+
+// Provides a playfield with a single row of invaders at y = 2 in the even columns from 2 to 16,
+// and the ship at y = 9.
+const DIMENSIONS: [u16; 2] = [20, 10];
+
+// Returns a tuning with the given timings and without bombs.
+fn tuning(march_millis: u64, shot_millis: u64) -> Gameplay {
+    Gameplay {
+        march_millis,
+        min_march_millis: 1,
+        shot_millis,
+        bomb_millis: 0,
+        ..Gameplay::default()
+    }
+}
+
+// Fires a single shot from the given column, and plays the given number of steps the way a game
+// does; returns where invaders were killed.
+fn fire(
+    column: u16,
+    gameplay: &Gameplay,
+    dimensions: &[u16],
+    weapon: Weapon,
+    steps: usize,
+) -> Vec<(u16, u16)> {
+    let mut player = Player::new(dimensions);
+    player.tune(gameplay);
+    player.arm(weapon);
+    while player.position().0 > column {
+        player.move_left();
+    }
+    while player.position().0 < column {
+        player.move_right();
+    }
+    let mut invaders = Invaders::with_gameplay(dimensions, gameplay);
+    assert_eq!(player.shoot(), 1);
+    let delta = Duration::from_millis(STEP_MILLIS);
+    let mut kills = Vec::new();
+    for _ in 0..steps {
+        player.update(delta);
+        invaders.update(delta);
+        kills.extend(player.detect_hit(&mut invaders).kills);
+    }
+    kills
+}
+
+#[test]
+fn hits_invader_descending_into_shot() {
+
+    // The shot rises a row every 12th step, from 3 to 2 in step 72, while the army marches
+    // every 18th step and descends from 2 to 3 in step 72, after three steps to the right.
+    let gameplay = tuning(85, 55);
+    assert_eq!(
+        fire(7, &gameplay, &DIMENSIONS, Weapon::Blaster, 80),
+        [(7, 3)]
+    );
+}
+
+#[test]
+fn hits_invader_marching_into_shot() {
+
+    // The army takes its first step to the right in step 72, as the shot rises into the row.
+    let gameplay = tuning(355, 55);
+    assert_eq!(
+        fire(5, &gameplay, &DIMENSIONS, Weapon::Blaster, 80),
+        [(5, 2)]
+    );
+}
+
+#[test]
+fn misses_invader_marching_out_of_the_way() {
+    let gameplay = tuning(355, 55);
+    assert!(fire(4, &gameplay, &DIMENSIONS, Weapon::Blaster, 120).is_empty());
+}
+
+#[test]
+fn hits_invader_fast_shot_jumps_over() {
+
+    // The shot rises five rows a step, from 8 to 3, and on to the top.
+    let gameplay = tuning(2000, 1);
+    assert_eq!(
+        fire(10, &gameplay, &DIMENSIONS, Weapon::Blaster, 2),
+        [(10, 2)]
+    );
+}
+
+#[test]
+fn stops_at_nearest_invader() {
+    let dimensions = [20, 14];
+    let gameplay = tuning(2000, 1);
+    assert_eq!(
+        fire(10, &gameplay, &dimensions, Weapon::Blaster, 10),
+        [(10, 4)]
+    );
+}
+
+#[test]
+fn pierces_everything_in_path() {
+    let dimensions = [20, 14];
+    let gameplay = tuning(2000, 1);
+    assert_eq!(
+        fire(10, &gameplay, &dimensions, Weapon::Laser, 10),
+        [(10, 4), (10, 2)]
+    );
+}
+
+#[test]
+fn spends_shots_that_reach_the_top() {
+    let gameplay = tuning(2000, 1);
+    let mut player = Player::new(&DIMENSIONS);
+    player.tune(&gameplay);
+    player.move_right();
+    let mut invaders = Invaders::with_gameplay(&DIMENSIONS, &gameplay);
+    player.shoot();
+    for _ in 0..2 {
+        player.update(Duration::from_millis(STEP_MILLIS));
+        assert_eq!(player.detect_hit(&mut invaders), Default::default());
+    }
+
+    // The shot flew past the army, and makes room for another once the blaster cooled down.
+    let gameplay = Gameplay {
+        shots: 1,
+        ..gameplay
+    };
+    player.tune(&gameplay);
+    player.update(Duration::from_millis(COOLDOWN_MILLIS));
+    assert_eq!(player.shoot(), 1);
+}
+
+#[test]
+fn caps_whole_volleys() {
+    let gameplay = Gameplay {
+        shots: 1,
+        ..tuning(2000, 2000)
+    };
+    let mut player = Player::new(&DIMENSIONS);
+    player.tune(&gameplay);
+    player.arm(Weapon::Spread);
+    assert_eq!(player.shoot(), 3);
+
+    // Another volley would take more shots into the air than the spread allows.
+    player.update(Duration::from_millis(200));
+    assert_eq!(player.shoot(), 0);
+}
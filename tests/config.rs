@@ -0,0 +1,168 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::Color;
+use terminal_invaders::audio::Sound;
+use terminal_invaders::config::{Config, ConfigWatcher};
+use terminal_invaders::frame::{new_frame, Drawable};
+use terminal_invaders::hud::Hud;
+use terminal_invaders::keys::Action;
+use terminal_invaders::player::Player;
+
+#[test]
+fn parses_example_config() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("res/config.example.toml");
+    let config = Config::load(Some(&path)).unwrap();
+    let defaults = Config::default();
+    assert_eq!(config.gameplay, defaults.gameplay);
+    assert_eq!(config.theme, defaults.theme);
+    assert_ne!(config.sound.patch(Sound::Shoot), Sound::Shoot.patch());
+    assert_eq!(config.sound.patch(Sound::Explode), Sound::Explode.patch());
+
+    // The example only adds a key to shoot with.
+    let ctrl_f = KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL);
+    assert_eq!(
+        config.key_map().unwrap().action(&ctrl_f),
+        Some(Action::Shoot)
+    );
+    assert_eq!(defaults.key_map().unwrap().action(&ctrl_f), None);
+}
+
+#[test]
+fn reports_invalid_configs() {
+    for (contents, error) in [
+        (
+            "[gameplay]\nshots = 0",
+            "gameplay.shots must be greater than 0",
+        ),
+        (
+            "[gameplay]\nshot_millis = 0",
+            "gameplay.shot_millis must be greater than 0",
+        ),
+        (
+            "[gameplay]\nmin_march_millis = 3000",
+            "must not exceed gameplay.march_millis",
+        ),
+        ("[gameplay]\nshots = \"many\"", "invalid type"),
+        ("[gameplay]\nspeed = 1", "unknown field `speed`"),
+        (
+            "[theme]\nplayer = \"AA\"",
+            "theme.player must be a single character",
+        ),
+        ("[theme]\nborder = \"sparkly\"", "border"),
+        ("[controls]\npreset = \"emacs\"", "unknown preset 'emacs'"),
+        (
+            "[controls.bindings]\nshoot = [\"hyper+x\"]",
+            "unknown modifier 'hyper'",
+        ),
+        (
+            "[sound.effects.laser]\nlayers = []",
+            "unknown sound effect sound.effects.laser",
+        ),
+        (
+            "[sound.effects.shoot]\nlayers = [[]]",
+            "sound.effects.shoot: must play at least one tone",
+        ),
+        (
+            "[sound.effects.shoot]\nlayers = [[{ waveform = \"sine\", start_hz = 440.0, end_hz = 440.0, seconds = 1e9, volume = 0.5 }]]",
+            "sound.effects.shoot: seconds must be between",
+        ),
+        (
+            "[sound.effects.shoot]\nlayers = [[{ waveform = \"sine\", start_hz = 440.0, end_hz = 440.0, seconds = 0.0, volume = 0.5 }]]",
+            "sound.effects.shoot: seconds must be between",
+        ),
+        (
+            "[sound.effects.shoot]\nlayers = [[{ waveform = \"sine\", start_hz = 440.0, end_hz = 1e9, seconds = 0.1, volume = 0.5 }]]",
+            "sound.effects.shoot: end_hz must be between 0 and 11025",
+        ),
+        (
+            "[sound.effects.shoot]\nlayers = [[{ waveform = \"sine\", start_hz = 440.0, end_hz = 440.0, seconds = 0.1, volume = -2.0 }]]",
+            "sound.effects.shoot: volume must be between 0 and 1",
+        ),
+        (
+            "[sound.effects.shoot]\nlayers = [[{ waveform = \"sine\", start_hz = 440.0, end_hz = 440.0, seconds = 3.0, volume = 0.5 }, { waveform = \"sine\", start_hz = 440.0, end_hz = 440.0, seconds = 3.0, volume = 0.5 }]]",
+            "a layer must not last longer than 5 seconds",
+        ),
+    ] {
+        let message = Config::parse(contents).unwrap_err().to_string();
+        assert!(
+            message.contains(error),
+            "{:?} reported {:?}",
+            contents,
+            message
+        );
+    }
+}
+
+#[test]
+fn requires_given_config_file() {
+    let missing = Path::new(env!("CARGO_MANIFEST_DIR")).join("res/missing.toml");
+    let message = Config::load(Some(&missing)).unwrap_err().to_string();
+    assert!(message.starts_with(&missing.display().to_string()));
+}
+
+#[test]
+fn applies_theme_glyphs_and_colors() {
+    let config = Config::parse(
+        "[theme]\nplayer = \"A\"\nshot = \"|\"\nforeground = \"green\"\nbackground = \"#000080\"",
+    )
+    .unwrap();
+    let dimensions = [12, 3];
+    let mut frame = new_frame(&dimensions);
+    let player = Player::new(&dimensions);
+    let mut hud = Hud::new(&dimensions);
+    hud.toast("△◦■");
+    player.draw(&mut frame, &config.theme);
+    hud.draw(&mut frame, &config.theme);
+
+    // Swaps the glyphs of the ship, but leaves the same characters in texts alone.
+    assert_eq!(frame[6][2], "A");
+    assert_eq!([&frame[0][0], &frame[1][0], &frame[2][0]], ["△", "◦", "■"]);
+    assert_eq!(config.theme.foreground, Some(Color::Green));
+    assert_eq!(config.theme.background, Color::Rgb { r: 0, g: 0, b: 128 });
+}
+
+#[test]
+fn reloads_changed_config_file() {
+    let dir = std::env::temp_dir().join(format!("terminal_invaders-watch-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.toml");
+    let mut watcher = ConfigWatcher::new(path.clone());
+    let poll = Duration::from_millis(501);
+
+    // Picks up a file that appears while playing, but only once.
+    fs::write(&path, "[gameplay]\nshots = 3").unwrap();
+    let config = watcher.poll(poll).unwrap().unwrap();
+    assert_eq!(config.gameplay().shots, 3);
+    assert!(watcher.poll(poll).is_none());
+
+    // Reports invalid changes without giving up on the file.
+    fs::write(&path, "[gameplay]\nshots = 0").unwrap();
+    let later = SystemTime::now() + Duration::from_secs(1);
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(later)
+        .unwrap();
+    assert!(watcher.poll(Duration::from_millis(100)).is_none());
+    assert!(watcher.poll(poll).unwrap().is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
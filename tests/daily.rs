@@ -0,0 +1,114 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+
+use terminal_invaders::config::{Gameplay, Theme};
+use terminal_invaders::daily::{self, DailyLog};
+use terminal_invaders::date::Date;
+use terminal_invaders::frame::{new_frame, Drawable, Frame};
+use terminal_invaders::game::{Game, Input};
+use terminal_invaders::leaderboard::Entry;
+use terminal_invaders::replay::{self, Replay};
+use terminal_invaders::{DAILY_HEIGHT, DAILY_WIDTH};
+
+fn challenge(date: &str) -> Game {
+    let seed = daily::seed(date.parse().unwrap());
+    let mut game = Game::new(
+        &[DAILY_WIDTH, DAILY_HEIGHT],
+        &Gameplay::default(),
+        false,
+        false,
+        seed,
+    );
+    game.scatter_armies();
+    game
+}
+
+fn drawn(game: &Game) -> Frame {
+    let mut frame = new_frame(&[DAILY_WIDTH, DAILY_HEIGHT]);
+    game.draw(&mut frame, &Theme::default());
+    frame
+}
+
+#[test]
+fn plays_the_same_challenge_all_day() {
+    let today = challenge("2022-06-01");
+    assert_eq!(drawn(&today), drawn(&challenge("2022-06-01")));
+    assert_ne!(drawn(&today), drawn(&challenge("2022-06-02")));
+    assert!(!today.invaders.all_killed());
+    assert_ne!(
+        daily::seed("2022-06-01".parse().unwrap()),
+        daily::seed("2022-06-02".parse().unwrap())
+    );
+}
+
+#[test]
+fn replays_challenges() {
+    let mut game = challenge("2022-06-01");
+    while !game.over() {
+        if game.ticks().is_multiple_of(10) {
+            game.input(Input::Shoot);
+        }
+        game.step();
+    }
+    let entry = Entry {
+        name: "AAA".to_string(),
+        score: game.scorecard.score,
+        wave: game.scorecard.wave,
+        accuracy: game.scorecard.accuracy(),
+        date: "2022-06-01".to_string(),
+        mode: "daily 2022-06-01".to_string(),
+        difficulty: "normal".to_string(),
+        replay: Replay::record(&game),
+    };
+    assert!(entry.replay.as_ref().unwrap().scattered);
+    assert!(replay::verify(&entry).is_ok());
+    let unscattered = Entry {
+        replay: entry.replay.clone().map(|replay| Replay {
+            scattered: false,
+            ..replay
+        }),
+        ..entry
+    };
+    assert!(replay::verify(&unscattered).is_err());
+}
+
+#[test]
+fn scores_one_attempt_a_day() {
+    let dir = std::env::temp_dir().join(format!("terminal_invaders-daily-{}", std::process::id()));
+    let path = dir.join("daily.toml");
+    let log = DailyLog::new(path.clone());
+    let today: Date = "2022-06-01".parse().unwrap();
+    let tomorrow = Date::from_days(today.days() + 1);
+
+    // Uses the attempt up as soon as it is taken, however the game goes.
+    assert!(log.available(today).unwrap());
+    assert!(log.claim(today).unwrap());
+    assert!(!log.available(today).unwrap());
+    assert!(!log.claim(today).unwrap());
+    assert!(log.claim(tomorrow).unwrap());
+    assert!(!log.claim(tomorrow).unwrap());
+
+    // Moves a corrupted log aside instead of costing the day's attempt.
+    let day_after = Date::from_days(tomorrow.days() + 1);
+    fs::write(&path, "last_scored = [").unwrap();
+    assert!(log.claim(day_after).unwrap());
+    assert_eq!(
+        fs::read_to_string(dir.join("daily.toml.corrupt")).unwrap(),
+        "last_scored = ["
+    );
+    assert!(!log.claim(day_after).unwrap());
+    fs::remove_dir_all(&dir).unwrap();
+}
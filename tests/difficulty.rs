@@ -0,0 +1,98 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use terminal_invaders::config::{Config, Gameplay};
+use terminal_invaders::difficulty::{Adaptive, Difficulty};
+use terminal_invaders::invaders::Invaders;
+
+#[test]
+fn overrides_difficulty_presets() {
+    let config = Config::parse("[gameplay]\ndifficulty = \"insane\"\nshots = 5").unwrap();
+    let insane = Difficulty::Insane.gameplay();
+    assert_eq!(config.gameplay(), Gameplay { shots: 5, ..insane });
+    assert_eq!(Config::default().gameplay(), Gameplay::default());
+
+    // Every preset is playable, and they get harder in order.
+    for pair in Difficulty::ALL.windows(2) {
+        let (easier, harder) = (pair[0].gameplay(), pair[1].gameplay());
+        assert!(easier.validate().is_ok());
+        assert!(harder.march_millis < easier.march_millis);
+        assert!(harder.shots < easier.shots);
+        assert!(harder.bomb_millis < easier.bomb_millis);
+    }
+    let message = Config::parse("[gameplay]\ndifficulty = \"brutal\"")
+        .unwrap_err()
+        .to_string();
+    assert!(message.contains("unknown variant `brutal`"), "{}", message);
+}
+
+#[test]
+fn adapts_march_to_accuracy_and_deaths() {
+    let gameplay = Gameplay::default();
+    let judge = Duration::from_millis(5001);
+
+    // Sharp shooting speeds the army up.
+    let mut adaptive = Adaptive::new();
+    for _ in 0..4 {
+        adaptive.record_shot();
+        adaptive.record_hit();
+    }
+    assert!(adaptive.update(judge));
+    assert!(adaptive.apply(&gameplay).march_millis < gameplay.march_millis);
+
+    // Missing and dying slow it down, but only so far.
+    let mut adaptive = Adaptive::new();
+    for _ in 0..20 {
+        adaptive.record_death();
+    }
+    assert_eq!(adaptive.pace(), 1.5);
+    assert_eq!(adaptive.apply(&gameplay).march_millis, 3000);
+    for _ in 0..4 {
+        adaptive.record_shot();
+    }
+    assert!(!adaptive.update(judge));
+}
+
+#[test]
+fn drops_bombs_onto_the_ship() {
+    let dimensions = [12, 12];
+    let mut invaders = Invaders::new(&dimensions);
+    invaders.tune(&Gameplay {
+        bomb_millis: 10,
+        ..Gameplay::default()
+    });
+    let mut hits = Vec::new();
+    for _ in 0..2000 {
+        invaders.update(Duration::from_millis(11));
+        for x in 0..dimensions[0] {
+            if invaders.bomb_hit(x, dimensions[1] - 1) {
+                hits.push(x);
+            }
+        }
+    }
+    assert!(!hits.is_empty());
+
+    // Without bombs, the sky stays clear.
+    let mut invaders = Invaders::new(&dimensions);
+    invaders.tune(&Gameplay {
+        bomb_millis: 0,
+        ..Gameplay::default()
+    });
+    for _ in 0..2000 {
+        invaders.update(Duration::from_millis(11));
+        assert!((0..dimensions[0]).all(|x| !invaders.bomb_hit(x, dimensions[1] - 1)));
+    }
+}
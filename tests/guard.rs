@@ -0,0 +1,36 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+
+use terminal_invaders::guard::{restore_terminal_to, save_crash_report};
+
+#[test]
+fn saves_crash_reports() {
+    let dir = std::env::temp_dir().join(format!("terminal_invaders-crash-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = save_crash_report(&dir, "message: boom").unwrap();
+    assert!(path.starts_with(&dir));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "message: boom");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn leaves_untouched_terminal_alone() {
+    // Restoring without ever entering raw mode must neither fail nor write escape codes.
+    let mut out = Vec::new();
+    restore_terminal_to(&mut out);
+    restore_terminal_to(&mut out);
+    assert!(out.is_empty());
+}
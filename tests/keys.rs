@@ -0,0 +1,123 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use terminal_invaders::keys::{Action, KeyBinding, KeyConfig, KeyMap, PRESETS};
+
+#[test]
+fn parses_and_displays_bindings() {
+    for binding in [
+        "left",
+        "space",
+        "f12",
+        "q",
+        "ctrl+c",
+        "ctrl+alt+delete",
+        "ctrl++",
+        "shift+left",
+    ] {
+        let parsed: KeyBinding = binding.parse().unwrap();
+        assert_eq!(parsed.to_string(), binding);
+    }
+    assert_eq!(
+        "Ctrl+X".parse::<KeyBinding>().unwrap(),
+        KeyBinding {
+            code: KeyCode::Char('X'),
+            modifiers: KeyModifiers::CONTROL,
+        }
+    );
+    assert!("hyper+x".parse::<KeyBinding>().is_err());
+    assert!("f13".parse::<KeyBinding>().is_err());
+
+    // Characters come shifted already, so shift would never match them.
+    assert!("shift+a".parse::<KeyBinding>().is_err());
+    assert!("ctrl+shift+space".parse::<KeyBinding>().is_err());
+    let shift_left: KeyBinding = "shift+left".parse().unwrap();
+    assert!(shift_left.matches(&KeyEvent::new(KeyCode::Left, KeyModifiers::SHIFT)));
+    assert!(!shift_left.matches(&KeyEvent::new(KeyCode::Left, KeyModifiers::NONE)));
+}
+
+#[test]
+fn matches_modifiers() {
+    let map = KeyConfig {
+        preset: Some("vim".to_string()),
+        bindings: [("shoot".to_string(), vec!["ctrl+f".to_string()])].into(),
+    }
+    .into_key_map()
+    .unwrap();
+    let ctrl_f = KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL);
+    let f = KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE);
+    let question = KeyEvent::new(KeyCode::Char('?'), KeyModifiers::SHIFT);
+    assert_eq!(map.action(&ctrl_f), Some(Action::Shoot));
+    assert_eq!(map.action(&f), None);
+    assert_eq!(map.action(&question), Some(Action::Help));
+    assert_eq!(
+        map.action(&KeyEvent::new(KeyCode::Char('h'), KeyModifiers::NONE)),
+        Some(Action::MoveLeft)
+    );
+}
+
+#[test]
+fn binds_every_action_in_every_preset() {
+    for preset in PRESETS {
+        let map = KeyMap::preset(preset).unwrap();
+        for action in Action::ALL {
+            assert!(
+                !map.keys(action).is_empty(),
+                "{} lacks {:?}",
+                preset,
+                action
+            );
+        }
+    }
+    assert!(KeyMap::preset("dvorak").is_none());
+}
+
+#[test]
+fn rejects_conflicting_bindings() {
+    for preset in PRESETS {
+        assert_eq!(KeyMap::preset(preset).unwrap().conflict(), None);
+    }
+    let config = |bindings: &[(&str, &str)]| KeyConfig {
+        preset: Some("wasd".to_string()),
+        bindings: bindings
+            .iter()
+            .map(|(action, key)| (action.to_string(), vec![key.to_string()]))
+            .collect(),
+    };
+
+    // Shadows a key of the preset, or claims the same key twice.
+    let error = config(&[("shoot", "q")]).into_key_map().unwrap_err();
+    assert_eq!(error.to_string(), "'q' is bound to both shoot and quit");
+    let error = config(&[("mute", "x"), ("boss", "ctrl+x"), ("help", "ctrl+x")])
+        .into_key_map()
+        .unwrap_err();
+    assert_eq!(error.to_string(), "'ctrl+x' is bound to both help and boss");
+
+    // Moving the preset's key out of the way first is fine, and so are other modifiers.
+    assert!(config(&[("shoot", "q"), ("quit", "esc")])
+        .into_key_map()
+        .is_ok());
+    assert!(config(&[("shoot", "ctrl+q")]).into_key_map().is_ok());
+}
+
+#[test]
+fn rejects_invalid_files() {
+    assert!(toml::from_str::<KeyConfig>("colors = true").is_err());
+    let unknown_action = KeyConfig {
+        preset: None,
+        bindings: [("jump".to_string(), vec!["space".to_string()])].into(),
+    };
+    assert!(unknown_action.into_key_map().is_err());
+}
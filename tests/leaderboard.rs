@@ -0,0 +1,247 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::thread;
+
+use terminal_invaders::config::Gameplay;
+use terminal_invaders::date::Date;
+use terminal_invaders::game::{Game, Input};
+use terminal_invaders::keys::Action;
+use terminal_invaders::leaderboard::{self, Entry, Leaderboard};
+use terminal_invaders::replay::{Rejection, Replay};
+use terminal_invaders::team::{self, Record, TeamLeaderboard};
+use terminal_invaders::LEADERBOARD_SIZE;
+
+fn scratch(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("terminal_invaders-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn entry(name: &str, score: u64, difficulty: &str) -> Entry {
+    Entry {
+        name: name.to_string(),
+        score,
+        wave: 1,
+        accuracy: 50,
+        date: "2022-06-01".to_string(),
+        mode: "classic".to_string(),
+        difficulty: difficulty.to_string(),
+        replay: None,
+    }
+}
+
+// Plays a short game that steers a little to the right and shoots every few steps.
+fn played(name: &str, seed: u64) -> Entry {
+    let gameplay = Gameplay {
+        march_millis: 100,
+        min_march_millis: 50,
+        ..Gameplay::default()
+    };
+    let mut game = Game::new(&[20, 12], &gameplay, false, false, seed);
+    for _ in 0..seed % 4 {
+        game.input(Input::Press(Action::MoveRight));
+    }
+    while !game.over() {
+        if game.ticks().is_multiple_of(20) {
+            game.input(Input::Shoot);
+        }
+        game.step();
+    }
+    Entry {
+        replay: Replay::record(&game),
+        score: game.scorecard.score,
+        wave: game.scorecard.wave,
+        accuracy: game.scorecard.accuracy(),
+        mode: "custom".to_string(),
+        ..entry(name, 0, "normal")
+    }
+}
+
+#[test]
+fn keeps_best_scores_per_table() {
+    let dir = scratch("scores");
+    let board = Leaderboard::new(dir.join("scores.toml"));
+    assert!(board.load().unwrap().is_empty());
+    for score in 1..=LEADERBOARD_SIZE as u64 + 2 {
+        board.record(entry("AAA", score * 10, "normal")).unwrap();
+    }
+    assert_eq!(board.record(entry("BBB", 5, "hard")).unwrap(), Some(1));
+    assert_eq!(board.record(entry("CCC", 15, "normal")).unwrap(), None);
+    assert_eq!(board.record(entry("DDD", 1000, "normal")).unwrap(), Some(1));
+
+    let entries = board.load().unwrap();
+    let tables = leaderboard::tables(&entries);
+    let normal = &tables[&("classic".to_string(), "normal".to_string())];
+    assert_eq!(normal.len(), LEADERBOARD_SIZE);
+    assert_eq!(normal[0].name, "DDD");
+    assert!(!leaderboard::qualifies(
+        &entries,
+        &entry("EEE", 20, "normal")
+    ));
+    assert!(leaderboard::qualifies(&entries, &entry("EEE", 1, "hard")));
+    assert!(leaderboard::format_tables(&entries).contains("classic (hard)"));
+    assert_eq!(leaderboard::format_tables(&[]), "No scores yet.\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn sets_corrupted_files_aside() {
+    let dir = scratch("corrupt");
+    let path = dir.join("scores.toml");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(&path, "version = 1\nentries = [[[").unwrap();
+    let board = Leaderboard::new(path.clone());
+    assert!(board.load().is_err());
+    assert_eq!(board.record(entry("AAA", 10, "normal")).unwrap(), Some(1));
+    assert_eq!(board.load().unwrap().len(), 1);
+    assert!(path.with_extension("toml.corrupt").exists());
+
+    // Shows the scores of a corrupted file as empty, and moves it aside as well.
+    fs::write(&path, "version = 1\nentries = [[[").unwrap();
+    let (entries, corrupt) = board.load_or_set_aside().unwrap();
+    assert!(entries.is_empty());
+    assert_eq!(corrupt, Some(path.with_extension("toml.corrupt")));
+    assert!(!path.exists());
+    assert_eq!(board.load_or_set_aside().unwrap(), (Vec::new(), None));
+
+    // Files of a newer game are left alone rather than overwritten.
+    fs::write(&path, "version = 99\n").unwrap();
+    assert!(board.load().is_err());
+    assert!(board.record(entry("AAA", 10, "normal")).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "version = 99\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn tolerates_concurrent_writers() {
+    let dir = scratch("concurrent");
+    let path = dir.join("scores.toml");
+    let writers: Vec<_> = (0..8)
+        .map(|writer| {
+            let board = Leaderboard::new(path.clone());
+            thread::spawn(move || board.record(entry("AAA", writer + 1, "normal")).unwrap())
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+    assert_eq!(Leaderboard::new(path.clone()).load().unwrap().len(), 8);
+
+    // A lock file left behind, e.g., by a crashed game, holds no lock.
+    fs::write(path.with_extension("toml.lock"), "").unwrap();
+    let board = Leaderboard::new(path);
+    assert_eq!(board.record(entry("AAA", 9, "normal")).unwrap(), Some(1));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn merges_team_records_and_rejects_forged_ones() {
+    let dir = scratch("team");
+    let cache = dir.join("cache").join("verified.toml");
+    let team = TeamLeaderboard::new(dir.clone()).with_cache(cache.clone());
+    assert!(team.load().unwrap().0.is_empty());
+    let first = team.submit(played("AAA", 1), 1).unwrap();
+    let second = played("BBB", 2);
+    let submitted = team.submit(second.clone(), 2).unwrap();
+    let original = fs::read_to_string(&submitted).unwrap();
+    let again = team.submit(second.clone(), 2).unwrap_err();
+    assert_eq!(again.kind(), io::ErrorKind::AlreadyExists);
+    assert_eq!(fs::read_to_string(&submitted).unwrap(), original);
+    let leftovers = fs::read_dir(&dir)
+        .unwrap()
+        .map(|file| file.unwrap().file_name())
+        .filter(|name| name.to_string_lossy().ends_with(".tmp"))
+        .count();
+    assert_eq!(leftovers, 0);
+
+    // Raises the score of the first record without updating its checksum.
+    let claimed = format!("score = {}", played("AAA", 1).score);
+    let tampered = fs::read_to_string(&first)
+        .unwrap()
+        .replace(&claimed, "score = 9000");
+    fs::write(&first, tampered).unwrap();
+
+    // Signs a raised score properly, which its replay does not back up.
+    let forged = Entry {
+        score: second.score + 10,
+        ..played("CCC", 3)
+    };
+    let forged = team.submit(forged, 3).unwrap();
+
+    // Keeps a record from before runs kept a replay.
+    fs::write(
+        dir.join("2022-05-31-OLD-0000000000000007.toml"),
+        "version = 1\nseed = \"0000000000000007\"\nchecksum = \"f9e9c180b599fe37\"\n\n\
+         [entry]\nname = \"OLD\"\nscore = 120\nwave = 2\naccuracy = 40\n\
+         date = \"2022-05-31\"\nmode = \"classic\"\ndifficulty = \"normal\"\n",
+    )
+    .unwrap();
+    fs::write(dir.join("notes.txt"), "not a record").unwrap();
+    fs::write(dir.join("broken.toml"), "entry = 1").unwrap();
+
+    // Loads only the runs that hold up when played again, and remembers them.
+    let old = dir.join("2022-05-31-OLD-0000000000000007.toml");
+    let (entries, rejected) = team.load().unwrap();
+    assert_eq!(entries, vec![second.clone()]);
+    let mut reasons: Vec<_> = rejected
+        .into_iter()
+        .map(|(path, rejection)| (path, matches!(rejection, Rejection::Invalid(_))))
+        .collect();
+    reasons.sort();
+    let mut expected = vec![
+        (first, true),
+        (forged, true),
+        (old.clone(), false),
+        (dir.join("broken.toml"), true),
+    ];
+    expected.sort();
+    assert_eq!(reasons, expected);
+    assert_eq!(
+        team::read(&old).unwrap().verify(),
+        Err(Rejection::Unverifiable("no replay".to_string()))
+    );
+    let remembered = fs::read_to_string(&cache).unwrap();
+    assert!(remembered.contains(&Record::new(second.clone(), 2).checksum));
+    assert_eq!(team.load().unwrap().0, vec![second.clone()]);
+
+    let record = Record::new(played("DDD", 4), 4);
+    assert!(record.verify().is_ok());
+    let other_seed = Record::new(played("DDD", 4), 5);
+    assert!(other_seed.is_valid());
+    assert!(other_seed.verify().is_err());
+
+    let local = vec![second.clone(), entry("EEE", 100_000, "normal")];
+    let merged = leaderboard::merge(local, vec![second.clone()]);
+    assert_eq!(merged.len(), 2);
+    assert_eq!(leaderboard::rank(&merged, &second), Some(1));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn converts_dates() {
+    assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+    for date in ["1970-01-01", "2000-02-29", "2024-12-31", "1969-07-20"] {
+        let parsed: Date = date.parse().unwrap();
+        assert_eq!(Date::from_days(parsed.days()), parsed);
+        assert_eq!(parsed.to_string(), date);
+    }
+    assert!("2023-02-29".parse::<Date>().is_err());
+    assert!("2022-13-01".parse::<Date>().is_err());
+    assert!("yesterday".parse::<Date>().is_err());
+}
@@ -0,0 +1,81 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use terminal_invaders::pacing::{self, Clock, ManualClock, Pacer};
+
+// Counts how often a pacer is ready within a second, checking every millisecond.
+fn runs_per_second(rate: u32) -> u32 {
+    let clock = ManualClock::new();
+    let mut pacer = Pacer::with_clock(rate, clock.clone());
+    let mut runs = 0;
+    for _ in 0..1000 {
+        clock.advance(Duration::from_millis(1));
+        if pacer.ready() {
+            runs += 1;
+        }
+    }
+    runs
+}
+
+#[test]
+fn runs_at_the_requested_rate() {
+    assert_eq!(runs_per_second(200), 200);
+    assert_eq!(runs_per_second(60), 60);
+    assert_eq!(Pacer::from_rate(0).interval(), Duration::from_secs(1));
+
+    // Sleeps for whatever is left of the interval.
+    let clock = ManualClock::new();
+    let start = clock.now();
+    let mut pacer = Pacer::with_clock(100, clock.clone());
+    clock.advance(Duration::from_millis(3));
+    pacer.wait();
+    assert_eq!(clock.now() - start, Duration::from_millis(10));
+    pacer.wait();
+    assert_eq!(clock.now() - start, Duration::from_millis(20));
+}
+
+#[test]
+fn moves_on_one_step_after_a_stall() {
+    let clock = ManualClock::new();
+    let mut pacer = Pacer::with_clock(100, clock.clone());
+
+    // Runs once for the missed intervals instead of bursting through all of them, and then
+    // keeps the pace from there.
+    clock.advance(Duration::from_secs(1));
+    assert!(pacer.ready());
+    assert!(!pacer.ready());
+    clock.advance(Duration::from_millis(9));
+    assert!(!pacer.ready());
+    clock.advance(Duration::from_millis(1));
+    assert!(pacer.ready());
+
+    // Waits a whole interval after a stall, rather than not at all.
+    clock.advance(Duration::from_secs(1));
+    pacer.wait();
+    let resumed = clock.now();
+    pacer.wait();
+    assert_eq!(clock.now() - resumed, Duration::from_millis(10));
+}
+
+#[test]
+fn lowers_rates_in_low_power_mode() {
+    let (ticks, frames) = pacing::rates(false);
+    let (low_ticks, low_frames) = pacing::rates(true);
+    assert!(low_ticks < ticks && low_frames < frames);
+    assert_eq!(runs_per_second(frames), frames);
+    assert_eq!(runs_per_second(low_frames), low_frames);
+    assert_eq!(runs_per_second(low_ticks), low_ticks);
+}
@@ -0,0 +1,152 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use terminal_invaders::config::{Gameplay, Theme};
+use terminal_invaders::frame::{new_frame, Drawable};
+use terminal_invaders::game::{Game, Input};
+use terminal_invaders::hud::Hud;
+use terminal_invaders::invaders::Invaders;
+use terminal_invaders::player::Player;
+use terminal_invaders::powerup::{Capsule, Power, PowerUps};
+use terminal_invaders::weapon::Weapon;
+use terminal_invaders::{COOLDOWN_MILLIS, POWER_UP_MILLIS};
+
+const DIMENSIONS: [u16; 2] = [20, 14];
+
+#[test]
+fn times_powers() {
+    let mut powers = PowerUps::new();
+    powers.activate(Power::SlowTime);
+    powers.activate(Power::ExtraLife);
+    powers.activate(Power::Spread);
+    assert!(powers.active(Power::SlowTime));
+    assert!(!powers.active(Power::ExtraLife));
+    assert!(powers.active(Power::Spread));
+
+    // Starts the timer over when caught again.
+    powers.update(Duration::from_millis(POWER_UP_MILLIS - 1000));
+    powers.activate(Power::Shield);
+    powers.activate(Power::SlowTime);
+    assert_eq!(
+        powers.update(Duration::from_millis(2000)),
+        vec![Power::Spread]
+    );
+    let left = Duration::from_millis(POWER_UP_MILLIS - 2000);
+    assert_eq!(
+        powers.remaining(),
+        vec![(Power::Shield, left), (Power::SlowTime, left)]
+    );
+    assert!(powers.consume(Power::Shield));
+    assert!(!powers.consume(Power::Shield));
+    powers.update(Duration::from_millis(POWER_UP_MILLIS));
+    assert!(powers.remaining().is_empty());
+}
+
+#[test]
+fn catches_capsules() {
+    let mut game = Game::new(&DIMENSIONS, &Gameplay::default(), false, false, 1);
+    let (x, y) = game.player.position();
+    let lives = game.lives;
+    game.capsules.push(Capsule::new(x, y - 2, Power::ExtraLife));
+    game.capsules.push(Capsule::new(x, y - 1, Power::SlowTime));
+    game.capsules.push(Capsule::new(x, y - 3, Power::Spread));
+    // Misses the capsule that falls beside the player.
+    let missed = Capsule::new(x + 3, y - 1, Power::Shield);
+    game.capsules.push(missed);
+    for _ in 0..200 {
+        game.step();
+    }
+    assert_eq!(game.lives, lives + 1);
+    assert!(game.player.powers.active(Power::SlowTime));
+    assert!(!game.player.powers.active(Power::Shield));
+    assert!(game.player.powers.active(Power::Spread));
+    assert_eq!(game.player.arsenal.weapon(), Weapon::Spread);
+    assert!(game.capsules.is_empty());
+}
+
+#[test]
+fn shoots_with_powers() {
+    let gameplay = Gameplay {
+        shots: 2,
+        shot_millis: 200,
+        ..Gameplay::default()
+    };
+    let cooldown = Duration::from_millis(COOLDOWN_MILLIS);
+    let mut player = Player::new(&DIMENSIONS);
+    player.tune(&gameplay);
+    assert_eq!(player.shoot(), 1);
+    player.update(cooldown);
+    assert_eq!(player.shoot(), 1);
+    player.update(cooldown);
+    assert_eq!(player.shoot(), 0);
+    player.power_up(Power::RapidFire);
+    assert_eq!(player.shoot(), 1);
+    player.power_up(Power::Spread);
+    assert_eq!(player.shoot(), 3);
+    assert_eq!(player.shoot(), 0);
+
+    // Switches back to the blaster once the powers ran out.
+    player.update(Duration::from_millis(POWER_UP_MILLIS + 1));
+    assert_eq!(player.arsenal.weapons(), vec![Weapon::Blaster]);
+}
+
+#[test]
+fn pierces_through_invaders() {
+    for (piercing, kills) in [(false, 1), (true, 2)] {
+        let mut player = Player::new(&DIMENSIONS);
+        let mut invaders = Invaders::new(&DIMENSIONS);
+        if piercing {
+            player.power_up(Power::Piercing);
+        }
+        player.shoot();
+        let mut killed = 0;
+        let mut hit = 0;
+        for _ in 0..40 {
+            player.update(Duration::from_millis(20));
+            let hits = player.detect_hit(&mut invaders);
+            killed += hits.kills.len();
+            hit += hits.shots;
+        }
+        assert_eq!((killed, hit), (kills, 1));
+    }
+}
+
+#[test]
+fn counts_piercing_shot_as_one_hit() {
+    let mut game = Game::new(&DIMENSIONS, &Gameplay::default(), false, false, 1);
+    game.player.power_up(Power::Piercing);
+    game.input(Input::Shoot);
+    for _ in 0..200 {
+        game.step();
+    }
+    assert_eq!((game.scorecard.fired, game.scorecard.hits), (1, 1));
+    assert_eq!(game.scorecard.score, 20);
+    assert_eq!(game.scorecard.accuracy(), 100);
+}
+
+#[test]
+fn shows_powers_on_hud() {
+    let dimensions = [30, 14];
+    let mut hud = Hud::new(&dimensions);
+    hud.set_powers(&[
+        (Power::Shield, Duration::from_millis(2500)),
+        (Power::SlowTime, Duration::from_millis(1000)),
+    ]);
+    let mut frame = new_frame(&dimensions);
+    hud.draw(&mut frame, &Theme::default());
+    let row: String = frame.iter().map(|col| col[0].as_str()).collect();
+    assert_eq!(row, "       shield 3s  slow 1s     ");
+}
@@ -0,0 +1,222 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use terminal_invaders::backend::{MemoryBackend, TextBackend};
+use terminal_invaders::config::Theme;
+use terminal_invaders::frame::{new_frame, Drawable};
+use terminal_invaders::invaders::Invaders;
+use terminal_invaders::player::Player;
+use terminal_invaders::render::{self, RenderStats, Sent};
+
+const DIMENSIONS: [u16; 2] = [12, 12];
+
+// Renders the player and invaders onto a fresh in-memory backend.
+fn snapshot(player: &Player, invaders: &Invaders) -> String {
+    let mut frame = new_frame(&DIMENSIONS);
+    let theme = Theme::default();
+    player.draw(&mut frame, &theme);
+    invaders.draw(&mut frame, &theme);
+    let mut backend = MemoryBackend::new(&DIMENSIONS);
+    render::render(&mut backend, &new_frame(&DIMENSIONS), &frame, true);
+    backend.to_text()
+}
+
+#[test]
+fn renders_initial_game() {
+    let player = Player::new(&DIMENSIONS);
+    let invaders = Invaders::new(&DIMENSIONS);
+    assert_eq!(
+        snapshot(&player, &invaders),
+        concat!(
+            "            \n",
+            "            \n",
+            "  ■ ■ ■ ■   \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "      △     \n",
+        )
+    );
+}
+
+#[test]
+fn renders_moving_player_and_shot() {
+    let mut player = Player::new(&DIMENSIONS);
+    let invaders = Invaders::new(&DIMENSIONS);
+    player.move_left();
+    player.move_up();
+    player.shoot();
+
+    // Timers only fire once more than their full duration elapsed.
+    for _ in 0..3 {
+        player.update(Duration::from_millis(51));
+    }
+    assert_eq!(
+        snapshot(&player, &invaders),
+        concat!(
+            "            \n",
+            "            \n",
+            "  ■ ■ ■ ■   \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "     ◦      \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "     △      \n",
+            "            \n",
+        )
+    );
+}
+
+#[test]
+fn renders_explosion_of_hit_invader() {
+    let mut player = Player::new(&DIMENSIONS);
+    let mut invaders = Invaders::new(&DIMENSIONS);
+    player.shoot();
+    for _ in 0..8 {
+        player.update(Duration::from_millis(51));
+        player.detect_hit(&mut invaders);
+    }
+    assert_eq!(
+        snapshot(&player, &invaders),
+        concat!(
+            "            \n",
+            "            \n",
+            "  ■ ■ ◉ ■   \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "      △     \n",
+        )
+    );
+    assert_eq!(invaders.alive(), 3);
+}
+
+#[test]
+fn renders_marching_army() {
+    let player = Player::new(&DIMENSIONS);
+    let mut invaders = Invaders::new(&DIMENSIONS);
+    invaders.update(Duration::from_millis(2001));
+    invaders.update(Duration::from_millis(1500));
+
+    // The army dropped a bomb on its way.
+    assert_eq!(
+        snapshot(&player, &invaders),
+        concat!(
+            "            \n",
+            "            \n",
+            "   □ □ □ □  \n",
+            "       ▾    \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "            \n",
+            "      △     \n",
+        )
+    );
+}
+
+#[test]
+fn renders_only_changed_cells() {
+    let mut backend = MemoryBackend::new(&DIMENSIONS);
+    let last_frame = new_frame(&DIMENSIONS);
+    let mut curr_frame = new_frame(&DIMENSIONS);
+    curr_frame[3][4] = "x".to_string();
+    curr_frame[7][1] = "y".to_string();
+    render::render(&mut backend, &last_frame, &curr_frame, false);
+    assert_eq!(
+        backend.take_draws(),
+        [(3, 4, "x".to_string()), (7, 1, "y".to_string())]
+    );
+
+    // Cells that did not change between the frames are left untouched.
+    let mut next_frame = curr_frame.clone();
+    next_frame[3][4] = " ".to_string();
+    render::render(&mut backend, &curr_frame, &next_frame, false);
+    assert_eq!(backend.take_draws(), [(3, 4, " ".to_string())]);
+    render::render(&mut backend, &next_frame, &next_frame, false);
+    assert!(backend.take_draws().is_empty());
+    assert_eq!(backend.cell(7, 1), 'y');
+
+    // Forcing redraws every cell.
+    render::render(&mut backend, &next_frame, &next_frame, true);
+    assert_eq!(backend.take_draws().len(), 12 * 12);
+}
+
+#[test]
+fn dumps_frames_as_text() {
+    let mut out = Vec::new();
+    let mut frame = new_frame(&[3, 2]);
+    frame[1][1] = "△".to_string();
+    {
+        let mut backend = TextBackend::new(&[3, 2], &mut out);
+        render::render(&mut backend, &frame, &frame, true);
+    }
+    assert_eq!(String::from_utf8(out).unwrap(), "   \n △ \n\x0c\n");
+}
+
+#[test]
+fn draws_latest_frame_and_reports_dropped_ones() {
+    let stats = RenderStats::new();
+    let (render_tx, render_rx) = render::mailbox();
+    let stale = new_frame(&DIMENSIONS);
+    let mut latest = new_frame(&DIMENSIONS);
+    latest[0][0] = "x".to_string();
+    assert_eq!(render::send_frame(&render_tx, stale, &stats), Sent::Queued);
+    assert_eq!(
+        render::send_frame(&render_tx, latest.clone(), &stats),
+        Sent::Queued
+    );
+    assert_eq!(stats.dropped(), 1);
+    assert_eq!(render::recv_latest(&render_rx), Some(latest.clone()));
+
+    // Hands the last frame over even after the game hung up.
+    render::send_frame(&render_tx, latest.clone(), &stats);
+    drop(render_tx);
+    assert_eq!(render::recv_latest(&render_rx), Some(latest));
+    assert_eq!(render::recv_latest(&render_rx), None);
+
+    let (render_tx, render_rx) = render::mailbox();
+    drop(render_rx);
+    let frame = new_frame(&DIMENSIONS);
+    assert_eq!(render::send_frame(&render_tx, frame, &stats), Sent::HungUp);
+}
+
+#[test]
+fn wakes_renderer_waiting_for_frame() {
+    let stats = RenderStats::new();
+    let (render_tx, render_rx) = render::mailbox();
+    let renderer = std::thread::spawn(move || render::recv_latest(&render_rx));
+    std::thread::sleep(Duration::from_millis(20));
+    let frame = new_frame(&DIMENSIONS);
+    render::send_frame(&render_tx, frame.clone(), &stats);
+    assert_eq!(renderer.join().unwrap(), Some(frame));
+}
@@ -0,0 +1,354 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use terminal_invaders::config::Gameplay;
+use terminal_invaders::daily;
+use terminal_invaders::date::Date;
+use terminal_invaders::difficulty::Difficulty;
+use terminal_invaders::game::{Game, Input};
+use terminal_invaders::keys::Action;
+use terminal_invaders::leaderboard::Entry;
+use terminal_invaders::replay::{self, Rejection, Replay, RULES_VERSION};
+use terminal_invaders::{DAILY_HEIGHT, DAILY_WIDTH, WAVES};
+
+// Plays a quick game with a custom tuning.
+fn play(seed: u64) -> Game {
+    let gameplay = Gameplay {
+        march_millis: 200,
+        min_march_millis: 50,
+        ..Gameplay::default()
+    };
+    sweep(Game::new(&[30, 16], &gameplay, true, true, seed))
+}
+
+// Plays the game to its end, sweeping the ship left and right and shooting every few steps.
+fn sweep(mut game: Game) -> Game {
+    while !game.over() {
+        match game.ticks() % 400 {
+            0 => game.input(Input::Press(Action::MoveLeft)),
+            100 => game.input(Input::Release(Action::MoveLeft)),
+            200 => game.input(Input::Press(Action::MoveRight)),
+            300 => game.input(Input::Release(Action::MoveRight)),
+            tick if tick % 15 == 0 => game.input(Input::Shoot),
+            _ => {}
+        }
+        game.step();
+    }
+    game
+}
+
+fn entry(game: &Game) -> Entry {
+    Entry {
+        name: "AAA".to_string(),
+        score: game.scorecard.score,
+        wave: game.scorecard.wave,
+        accuracy: game.scorecard.accuracy(),
+        date: "2022-06-01".to_string(),
+        mode: "custom".to_string(),
+        difficulty: "normal".to_string(),
+        replay: Replay::record(game),
+    }
+}
+
+#[test]
+fn logs_inputs_as_text() {
+    for input in [
+        Input::Press(Action::MoveUp),
+        Input::Release(Action::MoveLeft),
+        Input::ReleaseAll,
+        Input::Shoot,
+        Input::Aim(42),
+    ] {
+        assert_eq!(input.to_string().parse::<Input>().unwrap(), input);
+    }
+    for invalid in ["+quit", "-jump", "@x", "fire", ""] {
+        assert!(invalid.parse::<Input>().is_err());
+    }
+}
+
+#[test]
+fn replays_runs_exactly() {
+    let game = play(7);
+    assert!(game.scorecard.score > 0);
+    let entry = entry(&game);
+    assert_eq!(
+        replay::verify(&entry),
+        Ok(Duration::from_millis(5) * game.ticks() as u32)
+    );
+
+    // Survives the trip through the leaderboard file.
+    let contents = toml::to_string(&entry).unwrap();
+    let loaded: Entry = toml::from_str(&contents).unwrap();
+    assert_eq!(loaded, entry);
+
+    // Plays out the same again, down to the lives left.
+    let replayed = entry.replay.as_ref().unwrap().play().unwrap();
+    assert_eq!(replayed.scorecard, game.scorecard);
+    assert_eq!(replayed.lives, game.lives);
+}
+
+#[test]
+fn rejects_forged_runs() {
+    let game = play(11);
+    let honest = entry(&game);
+    let replay = honest.replay.clone().unwrap();
+    let forgeries = [
+        Entry {
+            score: honest.score + 10,
+            ..honest.clone()
+        },
+        Entry {
+            wave: honest.wave + 1,
+            ..honest.clone()
+        },
+        Entry {
+            replay: Some(Replay {
+                ticks: replay.ticks + 200,
+                ..replay.clone()
+            }),
+            ..honest.clone()
+        },
+        Entry {
+            replay: Some(Replay {
+                inputs: String::new(),
+                ..replay.clone()
+            }),
+            ..honest.clone()
+        },
+        Entry {
+            replay: Some(Replay {
+                inputs: format!("{} 0:shoot", replay.inputs),
+                ..replay.clone()
+            }),
+            ..honest.clone()
+        },
+        Entry {
+            replay: None,
+            ..honest.clone()
+        },
+    ];
+    for forged in forgeries {
+        assert!(replay::verify(&forged).is_err());
+    }
+}
+
+#[test]
+fn rejects_runs_off_their_table() {
+    let preset = Difficulty::Insane.gameplay();
+    let game = sweep(Game::new(&[30, 16], &preset, false, true, 3));
+    let classic = Entry {
+        mode: "classic".to_string(),
+        difficulty: "insane".to_string(),
+        ..entry(&game)
+    };
+    assert!(replay::verify(&classic).is_ok());
+    let replay = classic.replay.clone().unwrap();
+    let forgeries = [
+        Entry {
+            mode: "adaptive".to_string(),
+            ..classic.clone()
+        },
+        Entry {
+            difficulty: "easy".to_string(),
+            ..classic.clone()
+        },
+        Entry {
+            mode: "daily 2026-10-19".to_string(),
+            ..classic.clone()
+        },
+        Entry {
+            mode: "hardcore".to_string(),
+            ..classic.clone()
+        },
+        Entry {
+            replay: Some(Replay {
+                gameplay: Gameplay {
+                    lives: 1000,
+                    ..preset
+                },
+                ..replay.clone()
+            }),
+            ..classic.clone()
+        },
+    ];
+    for forged in forgeries {
+        assert!(matches!(
+            replay::verify(&forged),
+            Err(Rejection::Invalid(_))
+        ));
+    }
+
+    // Takes the day's challenge only with the day's seed, army, and playfield.
+    let date: Date = "2026-10-19".parse().unwrap();
+    let mut game = Game::new(
+        &[DAILY_WIDTH, DAILY_HEIGHT],
+        &preset,
+        false,
+        true,
+        daily::seed(date),
+    );
+    game.scatter_armies();
+    let daily = Entry {
+        mode: format!("daily {}", date),
+        difficulty: "insane".to_string(),
+        ..entry(&sweep(game))
+    };
+    assert!(replay::verify(&daily).is_ok());
+    let replay = daily.replay.clone().unwrap();
+    for forged in [
+        Replay {
+            seed: format!("{:016x}", daily::seed(date) ^ 1),
+            ..replay.clone()
+        },
+        Replay {
+            scattered: false,
+            ..replay.clone()
+        },
+    ] {
+        let forged = Entry {
+            replay: Some(forged),
+            ..daily.clone()
+        };
+        assert!(replay::verify(&forged).is_err());
+    }
+}
+
+#[test]
+fn bounds_the_game_played_again() {
+    let game = play(11);
+    let honest = entry(&game);
+    let replay = honest.replay.clone().unwrap();
+    for forged in [
+        Replay {
+            ticks: u64::MAX,
+            ..replay.clone()
+        },
+        Replay {
+            gameplay: Gameplay {
+                march_millis: u64::MAX,
+                min_march_millis: 1,
+                bomb_millis: 0,
+                ..replay.gameplay
+            },
+            ..replay.clone()
+        },
+    ] {
+        assert!(forged.play().is_err());
+    }
+}
+
+#[test]
+fn leaves_runs_of_other_rules_unverified() {
+    let game = play(11);
+    let honest = entry(&game);
+    let replay = honest.replay.clone().unwrap();
+    for (rules, reason) in [
+        (None, "older rules"),
+        (Some(RULES_VERSION - 1), "older rules"),
+        (Some(RULES_VERSION + 1), "newer rules"),
+    ] {
+        let other = Entry {
+            replay: Some(Replay {
+                rules,
+                ..replay.clone()
+            }),
+            ..honest.clone()
+        };
+        assert_eq!(
+            replay::verify(&other),
+            Err(Rejection::Unverifiable(reason.to_string()))
+        );
+    }
+
+    // Runs of the current rules that do not hold up are invalid, not just unverified.
+    let forged = Entry {
+        score: honest.score + 10,
+        ..honest
+    };
+    assert!(matches!(
+        replay::verify(&forged),
+        Err(Rejection::Invalid(_))
+    ));
+}
+
+#[test]
+fn advances_in_fixed_steps() {
+    let mut game = Game::new(&[30, 16], &Gameplay::default(), false, false, 1);
+    game.advance(Duration::from_millis(12));
+    assert_eq!(game.ticks(), 2);
+    game.advance(Duration::from_millis(3));
+    assert_eq!(game.ticks(), 3);
+
+    // Cannot replay a game whose tuning changed on the way.
+    game.tune(
+        &Gameplay {
+            shots: 1,
+            ..Gameplay::default()
+        },
+        false,
+    );
+    assert!(game.retuned());
+    assert!(Replay::record(&game).is_none());
+}
+
+#[test]
+fn steps_the_same_however_time_is_sliced() {
+    let start = |game: &mut Game| {
+        game.input(Input::Press(Action::MoveRight));
+        game.input(Input::Shoot);
+    };
+    let mut stepped = Game::new(&[30, 16], &Gameplay::default(), false, true, 5);
+    start(&mut stepped);
+    for _ in 0..2000 {
+        stepped.step();
+    }
+
+    // Advances by slices shorter and longer than a step, which add up to the same time.
+    let mut sliced = Game::new(&[30, 16], &Gameplay::default(), false, true, 5);
+    start(&mut sliced);
+    let slices = [1, 3, 7, 12, 2, 40, 5, 10];
+    let mut left = 2000 * 5;
+    for slice in slices.iter().cycle() {
+        let slice = left.min(*slice);
+        sliced.advance(Duration::from_millis(slice));
+        left -= slice;
+        if left == 0 {
+            break;
+        }
+    }
+    assert_eq!(sliced.ticks(), 2000);
+    assert_eq!(sliced.player.position(), stepped.player.position());
+    assert_eq!(sliced.scorecard, stepped.scorecard);
+    assert_eq!(sliced.lives, stepped.lives);
+    assert_eq!(Replay::record(&sliced), Replay::record(&stepped));
+}
+
+#[test]
+fn wins_after_last_wave() {
+    let mut game = Game::new(&[30, 16], &Gameplay::default(), false, false, 1);
+    while !game.over() {
+
+        // Clears the whole army at once.
+        for x in 0..30 {
+            for y in 0..16 {
+                game.invaders.kill_invader_at(x, y);
+            }
+        }
+        game.step();
+    }
+    assert!(game.won());
+    assert_eq!(game.scorecard.wave, WAVES);
+}
@@ -0,0 +1,53 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crossterm::style::Color;
+
+use terminal_invaders::config::Theme;
+use terminal_invaders::frame::new_frame;
+use terminal_invaders::screenshot::Screenshot;
+
+#[test]
+fn captures_text_and_ansi() {
+    let mut frame = new_frame(&[4, 2]);
+    frame[0][0] = "ab".to_string();
+    frame[2][1] = "△".to_string();
+    assert_eq!(frame.to_text(), "ab  \n  △ \n");
+    assert_eq!(
+        frame.to_ansi(&Theme::default()),
+        "\x1b[48;5;0m\x1b[39mab  \x1b[0m\n\x1b[48;5;0m\x1b[39m  △ \x1b[0m\n"
+    );
+
+    // Follows the colors of the theme.
+    let theme = Theme {
+        background: Color::Rgb { r: 0, g: 0, b: 64 },
+        foreground: Some(Color::Green),
+        ..Theme::default()
+    };
+    assert_eq!(
+        frame.to_ansi(&theme).lines().next(),
+        Some("\x1b[48;2;0;0;64m\x1b[38;5;10mab  \x1b[0m")
+    );
+}
+
+#[test]
+fn captures_svg_on_grid() {
+    let mut frame = new_frame(&[3, 2]);
+    frame[1][1] = "<".to_string();
+    let svg = frame.to_svg();
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"30\" height=\"40\""));
+    assert!(svg.contains("<text x=\"15\" y=\"36\">&lt;</text>"));
+    assert_eq!(svg.matches("<text").count(), 1);
+    assert!(frame.to_html().contains(&svg));
+}
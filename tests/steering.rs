@@ -0,0 +1,104 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use terminal_invaders::keys::Action;
+use terminal_invaders::player::Player;
+use terminal_invaders::steering::Steering;
+use terminal_invaders::MOVE_MILLIS;
+
+const DIMENSIONS: [u16; 2] = [20, 10];
+
+// Provides a time just long enough for the ship to move one more cell.
+const MOVE: Duration = Duration::from_millis(MOVE_MILLIS + 1);
+
+#[test]
+fn steps_once_per_press_without_release_events() {
+    let mut player = Player::new(&DIMENSIONS);
+    let mut steering = Steering::new(false);
+    steering.press(Action::MoveLeft, &mut player);
+    assert_eq!(player.position(), (9, 9));
+    steering.update(MOVE, &mut player);
+    assert_eq!(player.position(), (9, 9));
+
+    // The keyboard's auto-repeat arrives as more presses.
+    steering.press(Action::MoveLeft, &mut player);
+    steering.press(Action::MoveLeft, &mut player);
+    assert_eq!(player.position(), (7, 9));
+}
+
+#[test]
+fn keeps_moving_while_held() {
+    let mut player = Player::new(&DIMENSIONS);
+    let mut steering = Steering::new(true);
+    steering.press(Action::MoveRight, &mut player);
+    assert_eq!(player.position(), (11, 9));
+    steering.update(Duration::from_millis(MOVE_MILLIS / 2), &mut player);
+    assert_eq!(player.position(), (11, 9));
+    steering.update(MOVE, &mut player);
+    steering.update(MOVE, &mut player);
+    assert_eq!(player.position(), (13, 9));
+
+    // Holding another key moves diagonally, and pressing a held key again adds no speed.
+    steering.press(Action::MoveUp, &mut player);
+    steering.press(Action::MoveRight, &mut player);
+    assert_eq!(player.position(), (14, 8));
+    steering.update(MOVE, &mut player);
+    assert_eq!(player.position(), (15, 7));
+
+    // Stops once the keys are released.
+    steering.release(Action::MoveUp);
+    steering.update(MOVE, &mut player);
+    assert_eq!(player.position(), (16, 7));
+    steering.release(Action::MoveRight);
+    steering.update(MOVE, &mut player);
+    assert_eq!(player.position(), (16, 7));
+}
+
+#[test]
+fn stops_when_all_keys_are_released() {
+    let mut player = Player::new(&DIMENSIONS);
+    let mut steering = Steering::new(true);
+    steering.press(Action::MoveLeft, &mut player);
+    steering.press(Action::MoveUp, &mut player);
+    steering.release_all();
+    steering.update(MOVE, &mut player);
+    assert_eq!(player.position(), (9, 8));
+}
+
+#[test]
+fn follows_the_mouse() {
+    let mut player = Player::new(&DIMENSIONS);
+    let mut steering = Steering::new(false);
+    steering.aim(12);
+    for _ in 0..5 {
+        steering.update(MOVE, &mut player);
+    }
+    assert_eq!(player.position(), (12, 9));
+
+    // The keyboard takes over.
+    steering.aim(2);
+    steering.update(MOVE, &mut player);
+    steering.press(Action::MoveRight, &mut player);
+    steering.update(MOVE, &mut player);
+    assert_eq!(player.position(), (12, 9));
+
+    // Stops heading for the mouse once it is let go of.
+    steering.aim(2);
+    steering.update(MOVE, &mut player);
+    steering.release_all();
+    steering.update(MOVE, &mut player);
+    assert_eq!(player.position(), (11, 9));
+}
@@ -0,0 +1,40 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use terminal_invaders::audio::Sound;
+use terminal_invaders::synth::{Patch, Tone, Waveform, SAMPLE_RATE};
+
+#[test]
+fn renders_sequences_and_layers() {
+    let tone = Tone::new(Waveform::Square, 440.0, 220.0, 0.1, 0.5);
+    let sequence = Patch::sequence(vec![tone, tone]).render();
+    let layered = Patch::layered(vec![vec![tone], vec![tone, tone, tone]]).render();
+    let length = (0.1 * SAMPLE_RATE as f32) as usize;
+    assert_eq!(sequence.len(), 2 * length);
+    assert_eq!(layered.len(), 3 * length);
+    assert!(layered.iter().all(|sample| (-1.0..=1.0).contains(sample)));
+}
+
+#[test]
+fn synthesizes_every_sound_as_wav() {
+    for sound in Sound::ALL {
+        assert_eq!(sound.patch().validate(), Ok(()), "{}", sound.name());
+        let wav = sound.patch().to_wav();
+        let data_len = u32::from_le_bytes([wav[40], wav[41], wav[42], wav[43]]) as usize;
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(wav.len(), 44 + data_len);
+        assert!(data_len > 0, "{} should not be silent", sound.name());
+    }
+}
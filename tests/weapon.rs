@@ -0,0 +1,114 @@
+// Copyright 2021-2022 @nedifi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use terminal_invaders::config::Gameplay;
+use terminal_invaders::game::{Game, Input};
+use terminal_invaders::player::Player;
+use terminal_invaders::weapon::{Arsenal, Weapon};
+use terminal_invaders::STEP_MILLIS;
+
+const DIMENSIONS: [u16; 2] = [20, 14];
+
+#[test]
+fn fires_volleys_within_caps_and_cooldowns() {
+    let gameplay = Gameplay {
+        shots: 2,
+        shot_millis: 200,
+        ..Gameplay::default()
+    };
+    let cooldown = |weapon: Weapon| Duration::from_millis(weapon.spec(&gameplay).cooldown_millis);
+    let mut player = Player::new(&DIMENSIONS);
+    player.tune(&gameplay);
+    assert_eq!(player.shoot(), 1);
+    assert_eq!(player.shoot(), 0);
+    player.update(cooldown(Weapon::Blaster));
+    assert_eq!(player.shoot(), 1);
+    player.update(cooldown(Weapon::Blaster));
+    assert_eq!(player.shoot(), 0);
+
+    // Every weapon counts its own shots in the air, and cools down on its own.
+    player.arm(Weapon::Rapid);
+    for _ in 0..4 {
+        assert_eq!(player.shoot(), 1);
+        player.update(cooldown(Weapon::Rapid));
+    }
+    assert_eq!(player.shoot(), 0);
+
+    // The spread fires three at once, but has to cool down in between.
+    player.arm(Weapon::Spread);
+    assert_eq!(player.shoot(), 3);
+    assert_eq!(player.shoot(), 0);
+    player.update(cooldown(Weapon::Spread));
+    assert_eq!(player.shoot(), 3);
+}
+
+#[test]
+fn fires_rapid_faster_than_blaster() {
+    let gameplay = Gameplay {
+        shots: 100,
+        ..Gameplay::default()
+    };
+
+    // Holds the trigger down for a second.
+    let fired = |weapon: Weapon| {
+        let mut player = Player::new(&DIMENSIONS);
+        player.tune(&gameplay);
+        player.arm(weapon);
+        let mut fired = 0;
+        for _ in 0..1000 / STEP_MILLIS {
+            fired += player.shoot();
+            player.update(Duration::from_millis(STEP_MILLIS));
+        }
+        fired
+    };
+    assert_eq!(fired(Weapon::Blaster), 5);
+    assert_eq!(fired(Weapon::Rapid), 20);
+}
+
+#[test]
+fn cycles_and_drops_empty_weapons() {
+    let mut arsenal = Arsenal::default();
+    arsenal.arm(Weapon::Laser, Some(2));
+    arsenal.arm(Weapon::Rapid, Some(5));
+    assert_eq!(
+        arsenal.weapons(),
+        vec![Weapon::Blaster, Weapon::Laser, Weapon::Rapid]
+    );
+    assert_eq!((arsenal.weapon(), arsenal.ammo()), (Weapon::Rapid, Some(5)));
+    arsenal.cycle();
+    assert_eq!((arsenal.weapon(), arsenal.ammo()), (Weapon::Blaster, None));
+
+    // Catching a weapon again refills and selects it.
+    arsenal.arm(Weapon::Laser, Some(3));
+    let spec = Weapon::Laser.spec(&Gameplay::default());
+    for _ in 0..3 {
+        assert_eq!(arsenal.weapon(), Weapon::Laser);
+        arsenal.fire(&spec);
+    }
+    assert_eq!(arsenal.weapon(), Weapon::Blaster);
+    assert_eq!(arsenal.weapons(), vec![Weapon::Blaster, Weapon::Rapid]);
+    assert!(arsenal.ready());
+}
+
+#[test]
+fn logs_weapon_changes() {
+    let mut game = Game::new(&DIMENSIONS, &Gameplay::default(), false, false, 1);
+    game.player.arm(Weapon::Spread);
+    game.input("weapon".parse().unwrap());
+    assert_eq!(game.player.arsenal.weapon(), Weapon::Blaster);
+    assert_eq!(game.inputs(), &[(0, Input::CycleWeapon)]);
+    assert_eq!(Input::CycleWeapon.to_string(), "weapon");
+}